@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rs_aoc2025::day::solve_all;
+use rs_aoc2025::day11::Day11;
+
+// Parse and solve arbitrary text with every strategy.  Errors are fine; panics aren't.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        solve_all(&Day11::new(), text);
+    }
+});
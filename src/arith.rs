@@ -0,0 +1,188 @@
+// Overflow-checked arithmetic for the solvers.
+//
+// Solvers whose sums and products can outgrow a usize are generic over Arith, and report
+// SolveError::Overflow when a usize (or u128) isn't big enough.  The overflow policy decides what
+// happens then: report it, or rerun the solver in u128, then in BigUint.
+
+use std::cell::Cell;
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, ToPrimitive};
+
+use crate::day::{Answer, SolveError};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Policy {
+    // Overflow is an error.
+    Checked,
+
+    // Retry in u128 after an overflow.
+    Wide,
+
+    // Retry in u128, then BigUint, which can't overflow.
+    Big,
+}
+
+impl Policy {
+    pub fn parse(s: &str) -> Option<Policy> {
+        match s {
+            "checked" => Some(Policy::Checked),
+            "wide" => Some(Policy::Wide),
+            "big" => Some(Policy::Big),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    static POLICY: Cell<Policy> = const { Cell::new(Policy::Checked) };
+}
+
+// The overflow policy for solvers run on this thread.
+pub fn policy() -> Policy {
+    POLICY.with(|p| { p.get() })
+}
+
+// Set the overflow policy for solvers run on this thread.  Returns the old one.
+pub fn set_policy(policy: Policy) -> Policy {
+    POLICY.with(|p| { p.replace(policy) })
+}
+
+// An unsigned integer type a solver can do its arithmetic in.
+pub trait Arith: Clone + Ord + fmt::Debug + fmt::Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv {
+    fn from_usize(n: usize) -> Self;
+
+    // None if n doesn't fit.
+    fn from_big(n: &BigUint) -> Option<Self>;
+
+    fn into_answer(self) -> Answer;
+}
+
+impl Arith for usize {
+    fn from_usize(n: usize) -> Self {
+        n
+    }
+
+    fn from_big(n: &BigUint) -> Option<Self> {
+        n.to_usize()
+    }
+
+    fn into_answer(self) -> Answer {
+        Answer::Numeric(self)
+    }
+}
+
+impl Arith for u128 {
+    fn from_usize(n: usize) -> Self {
+        n as u128
+    }
+
+    fn from_big(n: &BigUint) -> Option<Self> {
+        n.to_u128()
+    }
+
+    fn into_answer(self) -> Answer {
+        match usize::try_from(self) {
+            Ok(n) => Answer::Numeric(n),
+            Err(_) => Answer::Big(BigUint::from(self)),
+        }
+    }
+}
+
+impl Arith for BigUint {
+    fn from_usize(n: usize) -> Self {
+        BigUint::from(n)
+    }
+
+    fn from_big(n: &BigUint) -> Option<Self> {
+        Some(n.clone())
+    }
+
+    fn into_answer(self) -> Answer {
+        match self.to_usize() {
+            Some(n) => Answer::Numeric(n),
+            None => Answer::Big(self),
+        }
+    }
+}
+
+// Run a solver in usize.  If it overflows, and the policy allows, run it again in u128, and
+// again in BigUint.  The three closures should be the same solver instantiated for each type.
+pub fn widen<R>(narrow: impl FnOnce() -> Result<R, SolveError>,
+                wide: impl FnOnce() -> Result<R, SolveError>,
+                big: impl FnOnce() -> Result<R, SolveError>) -> Result<R, SolveError> {
+    let policy = policy();
+
+    match narrow() {
+        Err(SolveError::Overflow { .. }) if policy != Policy::Checked => {
+            match wide() {
+                Err(SolveError::Overflow { .. }) if policy == Policy::Big => big(),
+                result => result,
+            }
+        }
+        result => result,
+    }
+}
+
+// Run a solver once, in the widest type the policy allows.  For solvers that can't be rerun,
+// like those reading a stream, which is passed to whichever closure runs.
+pub fn widest<I: ?Sized, R>(input: &mut I,
+                            narrow: impl FnOnce(&mut I) -> Result<R, SolveError>,
+                            wide: impl FnOnce(&mut I) -> Result<R, SolveError>,
+                            big: impl FnOnce(&mut I) -> Result<R, SolveError>) -> Result<R, SolveError> {
+    match policy() {
+        Policy::Checked => narrow(input),
+        Policy::Wide => wide(input),
+        Policy::Big => big(input),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use crate::arith::{set_policy, widen, Arith, Policy};
+    use crate::day::{Answer, SolveError};
+
+    // Sum of squares, the way a solver would do it.
+    fn sum_squares<T: Arith>(values: &[usize]) -> Result<Answer, SolveError> {
+        let mut sum = T::from_usize(0);
+        for v in values {
+            let v = T::from_usize(*v);
+            sum = v.checked_mul(&v)
+                .and_then(|square| { sum.checked_add(&square) })
+                .ok_or(SolveError::overflow(0, "sum of squares"))?;
+        }
+
+        Ok(sum.into_answer())
+    }
+
+    fn solve(values: &[usize]) -> Result<Answer, SolveError> {
+        widen(|| { sum_squares::<usize>(values) },
+              || { sum_squares::<u128>(values) },
+              || { sum_squares::<BigUint>(values) })
+    }
+
+    #[test]
+    fn test_widen() {
+        let small = [3, 4];
+        let wide = [usize::MAX, 2];
+        let big = [usize::MAX; 3];
+        let big_answer = BigUint::from(usize::MAX).pow(2) * BigUint::from(3_usize);
+
+        set_policy(Policy::Checked);
+        assert_eq!(solve(&small), Ok(Answer::Numeric(25)));
+        assert_eq!(solve(&wide), Err(SolveError::overflow(0, "sum of squares")));
+
+        set_policy(Policy::Wide);
+        assert_eq!(solve(&wide), Ok(Answer::Big(BigUint::from(usize::MAX).pow(2) + BigUint::from(4_usize))));
+        assert_eq!(solve(&big), Err(SolveError::overflow(0, "sum of squares")));
+
+        set_policy(Policy::Big);
+        assert_eq!(solve(&small), Ok(Answer::Numeric(25)));
+        assert_eq!(solve(&big), Ok(Answer::Big(big_answer)));
+
+        set_policy(Policy::Checked);
+    }
+}
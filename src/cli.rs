@@ -0,0 +1,564 @@
+// Command line handling.
+//
+//   rs_aoc2025                                     report all days
+//   rs_aoc2025 <day> [--part <n>] [--strategy <name>] [--input <file>] [--check]
+//                                                  report one day, optionally checking the answers
+//   rs_aoc2025 diff [<day>]                        run every strategy, report disagreements
+//   rs_aoc2025 generate <day> [--size <n>] [--seed <n>]  print a random input for a day
+//   rs_aoc2025 stream <day> --part <n> [<file>]    solve a part reading input as it goes
+//   rs_aoc2025 lint <day> [<file>]                 check an input against the day's assumptions
+//   rs_aoc2025 serve [--port <n>]                  serve the solvers over HTTP on localhost
+//   rs_aoc2025 dash [--timeout <secs>]             interactive dashboard running every day
+//   rs_aoc2025 watch <day> [<file>]                re-run a day's tests and parts when its files change
+//   rs_aoc2025 record [<day>]                      time every part and add the run to the history
+//   rs_aoc2025 compare [<day>] [--threshold <pct>] [--against <commit>]
+//                                                  flag parts slower or answering differently than before
+//   rs_aoc2025 html [<file>]                       write an HTML report of a run of every day
+//   rs_aoc2025 vis <day> [<file>] [--svg] [--frames <dir>]
+//                                                  draw how a day works through its input
+//   rs_aoc2025 repl                                interactive prompt for trying solvers on inputs
+//   rs_aoc2025 ids [<file>] [--base <n>] [--repeats <min>[-[<max>]]] [--exact]
+//                  [--palindromes | --non-decreasing | --pattern <regex>]
+//                  [--overlaps given|merge|reject] [--breakdown]
+//                                                  count and sum day 2's invalid ids under other rules
+//
+// Any of these can also take --overflow checked|wide|big, for what solvers do on overflow.
+
+use rs_aoc2025::arith::Policy;
+use rs_aoc2025::day2::Overlaps;
+use rs_aoc2025::vis::Style;
+
+pub const USAGE: &str = "\
+usage: rs_aoc2025 [<day>] [--part <n>] [--strategy <name>] [--input <file>] [--check]
+       rs_aoc2025 diff [<day>]
+       rs_aoc2025 generate <day> [--size <n>] [--seed <n>]
+       rs_aoc2025 stream <day> --part <n> [<file>]
+       rs_aoc2025 lint <day> [<file>]
+       rs_aoc2025 serve [--port <n>]
+       rs_aoc2025 dash [--timeout <secs>]
+       rs_aoc2025 watch <day> [<file>]
+       rs_aoc2025 record [<day>]
+       rs_aoc2025 compare [<day>] [--threshold <pct>] [--against <commit>]
+       rs_aoc2025 html [<file>]
+       rs_aoc2025 vis <day> [<file>] [--svg] [--frames <dir>]
+       rs_aoc2025 repl
+       rs_aoc2025 ids [<file>] [--base <n>] [--repeats <min>[-[<max>]]] [--exact]
+                      [--palindromes | --non-decreasing | --pattern <regex>]
+                      [--overlaps given|merge|reject] [--breakdown]
+options: --overflow checked|wide|big   on overflow: report it (default), or retry in u128 or bignums";
+
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    // Print answers for one day, or all of them.
+    // A day can be solved for another input, and its answers checked against the expected ones.
+    Report { day: Option<usize>, part: Option<usize>, strategy: Option<String>, input: Option<String>, check: bool },
+
+    // Run every registered strategy and compare their answers.
+    Diff { day: Option<usize> },
+
+    // Print a random input for a day.
+    Generate { day: usize, size: usize, seed: u64 },
+
+    // Solve one part reading the input from a file (or stdin) as it goes.
+    Stream { day: usize, part: usize, file: Option<String> },
+
+    // Check an input file (or the day's puzzle input) without solving it.
+    Lint { day: usize, file: Option<String> },
+
+    // Serve the solvers over HTTP.
+    Serve { port: u16 },
+
+    // Run every day in an interactive dashboard, giving up on parts that take over `timeout` seconds.
+    Dash { timeout: u64 },
+
+    // Re-run a day's tests and parts whenever its source or input file changes.
+    Watch { day: usize, file: Option<String> },
+
+    // Time every part of a day (or all of them) and add the results to the history.
+    Record { day: Option<usize> },
+
+    // Time parts again and compare them with the history: the latest run, or the latest from a commit.
+    Compare { day: Option<usize>, threshold: u32, against: Option<String> },
+
+    // Run every day and write an HTML report of the run to a file.
+    Html { file: String },
+
+    // Draw a day working through an input file (or its puzzle input.)  The last frame goes to
+    // stdout, or every frame to files in a directory.
+    Vis { day: usize, file: Option<String>, style: Style, frames: Option<String> },
+
+    // Read commands for trying the solvers on inputs, until quit.
+    Repl,
+
+    // Count and sum day 2's invalid ids in a file (or its puzzle input) under another rule, with
+    // ids written in `base`, dealing with overlapping ranges as `overlaps` says.  With
+    // `breakdown`, range by range too.
+    Ids { file: Option<String>, base: u32, kind: IdKind, overlaps: Overlaps, breakdown: bool },
+}
+
+// Which ids the ids command picks out.
+#[derive(PartialEq, Debug)]
+pub enum IdKind {
+    // A kernel repeated from `min_repeats` to `max_repeats` times, the shortest kernel when `exact`.
+    Repeats { min_repeats: usize, max_repeats: usize, exact: bool },
+
+    Palindromes,
+
+    NonDecreasing,
+
+    // Digits matching a regular expression.
+    Pattern(String),
+}
+
+fn parse_day(s: &str, num_days: usize) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(day) if (1..=num_days).contains(&day) => Ok(day),
+        _ => Err(format!("Day {s} is invalid.")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<&str>) -> Result<T, String> {
+    let value = value.ok_or(format!("{option} needs a value."))?;
+    value.parse::<T>().map_err(|_| { format!("Bad value '{value}' for {option}.") })
+}
+
+fn parse_part(s: &str) -> Result<usize, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("Part {s} is invalid.")),
+    }
+}
+
+// Parse repeat counts like "2", "2-4" or "2-" (no most.)
+fn parse_repeats(value: Option<&str>) -> Result<(usize, usize), String> {
+    let value = value.ok_or("--repeats needs a value.")?;
+    let bad = || { format!("Bad value '{value}' for --repeats.") };
+
+    let (min, max) = match value.split_once('-') {
+        Some((min, "")) => (min, None),
+        Some((min, max)) => (min, Some(max)),
+        None => (value, Some(value)),
+    };
+    let min = min.parse::<usize>().map_err(|_| { bad() })?;
+    let max = match max {
+        Some(max) => max.parse::<usize>().map_err(|_| { bad() })?,
+        None => usize::MAX,
+    };
+
+    Ok((min, max))
+}
+
+// Remove --overflow and its value from the arguments, wherever it is, and return the policy.
+pub fn take_overflow(args: &mut Vec<String>) -> Result<Policy, String> {
+    let Some(at) = args.iter().position(|arg| { arg == "--overflow" }) else {
+        return Ok(Policy::Checked);
+    };
+    let value = args.get(at + 1).ok_or("--overflow needs a value.")?;
+    let policy = Policy::parse(value).ok_or(format!("Bad value '{value}' for --overflow."))?;
+    args.drain(at..at + 2);
+
+    Ok(policy)
+}
+
+// Parse the arguments (not including the program name.)
+pub fn parse(args: &[String], num_days: usize) -> Result<Command, String> {
+    let mut args = args.iter().map(|s| { s.as_str() });
+
+    match args.next() {
+        Some("diff") => {
+            let day = match args.next() {
+                Some(s) => Some(parse_day(s, num_days)?),
+                None => None,
+            };
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Diff { day })
+        }
+        Some("generate") => {
+            let day = parse_day(args.next().ok_or("generate needs a day.")?, num_days)?;
+            let mut size = 20;
+            let mut seed = 0;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--size" => size = parse_number(arg, args.next())?,
+                    "--seed" => seed = parse_number(arg, args.next())?,
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            Ok(Command::Generate { day, size, seed })
+        }
+        Some("stream") => {
+            let day = parse_day(args.next().ok_or("stream needs a day.")?, num_days)?;
+            let mut part = None;
+            let mut file = None;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--part" => {
+                        let value = args.next().ok_or("--part needs a value.")?;
+                        part = Some(parse_part(value)?);
+                    }
+                    _ if file.is_none() && !arg.starts_with("--") => file = Some(arg.to_string()),
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+            let part = part.ok_or("stream needs --part.")?;
+
+            Ok(Command::Stream { day, part, file })
+        }
+        Some("lint") => {
+            let day = parse_day(args.next().ok_or("lint needs a day.")?, num_days)?;
+            let file = args.next().map(|s| { s.to_string() });
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Lint { day, file })
+        }
+        Some("serve") => {
+            let mut port = 8025;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--port" => port = parse_number(arg, args.next())?,
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            Ok(Command::Serve { port })
+        }
+        Some("dash") => {
+            let mut timeout = 30;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--timeout" => timeout = parse_number(arg, args.next())?,
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            Ok(Command::Dash { timeout })
+        }
+        Some("watch") => {
+            let day = parse_day(args.next().ok_or("watch needs a day.")?, num_days)?;
+            let file = args.next().map(|s| { s.to_string() });
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Watch { day, file })
+        }
+        Some("record") => {
+            let day = match args.next() {
+                Some(s) => Some(parse_day(s, num_days)?),
+                None => None,
+            };
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Record { day })
+        }
+        Some("compare") => {
+            let mut day = None;
+            let mut threshold = 25;
+            let mut against = None;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--threshold" => threshold = parse_number(arg, args.next())?,
+                    "--against" => {
+                        let value = args.next().ok_or("--against needs a value.")?;
+                        against = Some(value.to_string());
+                    }
+                    _ if day.is_none() && !arg.starts_with("--") => day = Some(parse_day(arg, num_days)?),
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            Ok(Command::Compare { day, threshold, against })
+        }
+        Some("html") => {
+            let file = args.next().unwrap_or(crate::html::REPORT_FILE).to_string();
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Html { file })
+        }
+        Some("vis") => {
+            let day = parse_day(args.next().ok_or("vis needs a day.")?, num_days)?;
+            let mut file = None;
+            let mut style = Style::Ascii;
+            let mut frames = None;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--svg" => style = Style::Svg,
+                    "--frames" => {
+                        let value = args.next().ok_or("--frames needs a value.")?;
+                        frames = Some(value.to_string());
+                    }
+                    _ if file.is_none() && !arg.starts_with("--") => file = Some(arg.to_string()),
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            Ok(Command::Vis { day, file, style, frames })
+        }
+        Some("repl") => {
+            if let Some(extra) = args.next() {
+                return Err(format!("Unexpected argument '{extra}'."));
+            }
+
+            Ok(Command::Repl)
+        }
+        Some("ids") => {
+            let mut file = None;
+            let mut base = 10;
+            let (mut min_repeats, mut max_repeats) = (2, usize::MAX);
+            let mut exact = false;
+            let mut repeats = false;
+            let mut kinds = Vec::new();
+            let mut overlaps = Overlaps::AsGiven;
+            let mut breakdown = false;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--base" => base = parse_number(arg, args.next())?,
+                    "--repeats" => {
+                        (min_repeats, max_repeats) = parse_repeats(args.next())?;
+                        repeats = true;
+                    }
+                    "--exact" => (exact, repeats) = (true, true),
+                    "--palindromes" => kinds.push(IdKind::Palindromes),
+                    "--non-decreasing" => kinds.push(IdKind::NonDecreasing),
+                    "--pattern" => {
+                        let value = args.next().ok_or("--pattern needs a value.")?;
+                        kinds.push(IdKind::Pattern(value.to_string()));
+                    }
+                    "--overlaps" => {
+                        let value = args.next().ok_or("--overlaps needs a value.")?;
+                        overlaps = Overlaps::parse(value).ok_or(format!("Bad value '{value}' for --overlaps."))?;
+                    }
+                    "--breakdown" => breakdown = true,
+                    _ if file.is_none() && !arg.starts_with("--") => file = Some(arg.to_string()),
+                    _ => return Err(format!("Unexpected argument '{arg}'.")),
+                }
+            }
+
+            let kind = match kinds.pop() {
+                None => IdKind::Repeats { min_repeats, max_repeats, exact },
+                Some(kind) if kinds.is_empty() && !repeats => kind,
+                Some(_) => return Err(String::from("ids takes one of --repeats (or --exact), --palindromes, --non-decreasing and --pattern.")),
+            };
+
+            Ok(Command::Ids { file, base, kind, overlaps, breakdown })
+        }
+        first => {
+            let mut day = None;
+            let mut part = None;
+            let mut strategy = None;
+            let mut input = None;
+            let mut check = false;
+
+            let mut next = first;
+            while let Some(arg) = next {
+                match arg {
+                    "--part" => {
+                        let value = args.next().ok_or("--part needs a value.")?;
+                        part = Some(parse_part(value)?);
+                    }
+                    "--strategy" => {
+                        let value = args.next().ok_or("--strategy needs a value.")?;
+                        strategy = Some(value.to_string());
+                    }
+                    "--input" => {
+                        let value = args.next().ok_or("--input needs a value.")?;
+                        input = Some(value.to_string());
+                    }
+                    "--check" => check = true,
+                    _ if day.is_none() && !arg.starts_with("--") => {
+                        day = Some(parse_day(arg, num_days)?);
+                    }
+                    _ => {
+                        return Err(format!("Unexpected argument '{arg}'."));
+                    }
+                }
+                next = args.next();
+            }
+
+            if day.is_none() && (part.is_some() || strategy.is_some() || input.is_some()) {
+                return Err(String::from("--part, --strategy and --input need a day."));
+            }
+
+            Ok(Command::Report { day, part, strategy, input, check })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rs_aoc2025::arith::Policy;
+    use rs_aoc2025::day2::Overlaps;
+    use rs_aoc2025::vis::Style;
+
+    use crate::cli::{parse, take_overflow, Command, IdKind};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| { a.to_string() }).collect()
+    }
+
+    #[test]
+    fn test_parse_report() {
+        assert_eq!(parse(&args(""), 12),
+                   Ok(Command::Report { day: None, part: None, strategy: None, input: None, check: false }));
+        assert_eq!(parse(&args("10 --part 1 --strategy linalg"), 12),
+                   Ok(Command::Report { day: Some(10), part: Some(1), strategy: Some("linalg".to_string()), input: None, check: false }));
+        assert_eq!(parse(&args("--check 3 --input mine.txt"), 12),
+                   Ok(Command::Report { day: Some(3), part: None, strategy: None, input: Some("mine.txt".to_string()), check: true }));
+        assert_eq!(parse(&args("--check"), 12),
+                   Ok(Command::Report { day: None, part: None, strategy: None, input: None, check: true }));
+        assert!(parse(&args("13"), 12).is_err());
+        assert!(parse(&args("--part 1"), 12).is_err());
+        assert!(parse(&args("--input mine.txt"), 12).is_err());
+        assert!(parse(&args("10 --part 3"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_diff() {
+        assert_eq!(parse(&args("diff"), 12), Ok(Command::Diff { day: None }));
+        assert_eq!(parse(&args("diff 9"), 12), Ok(Command::Diff { day: Some(9) }));
+        assert!(parse(&args("diff 9 10"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        assert_eq!(parse(&args("generate 4"), 12), Ok(Command::Generate { day: 4, size: 20, seed: 0 }));
+        assert_eq!(parse(&args("generate 4 --seed 7 --size 100"), 12),
+                   Ok(Command::Generate { day: 4, size: 100, seed: 7 }));
+        assert!(parse(&args("generate"), 12).is_err());
+        assert!(parse(&args("generate 4 --size lots"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        assert_eq!(parse(&args("stream 1 --part 2"), 12), Ok(Command::Stream { day: 1, part: 2, file: None }));
+        assert_eq!(parse(&args("stream 5 moves.txt --part 1"), 12),
+                   Ok(Command::Stream { day: 5, part: 1, file: Some("moves.txt".to_string()) }));
+        assert!(parse(&args("stream 1"), 12).is_err());
+        assert!(parse(&args("stream 1 --part 1 a.txt b.txt"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_lint() {
+        assert_eq!(parse(&args("lint 10"), 12), Ok(Command::Lint { day: 10, file: None }));
+        assert_eq!(parse(&args("lint 11 devices.txt"), 12),
+                   Ok(Command::Lint { day: 11, file: Some("devices.txt".to_string()) }));
+        assert!(parse(&args("lint"), 12).is_err());
+        assert!(parse(&args("lint 11 a.txt b.txt"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        assert_eq!(parse(&args("serve"), 12), Ok(Command::Serve { port: 8025 }));
+        assert_eq!(parse(&args("serve --port 9000"), 12), Ok(Command::Serve { port: 9000 }));
+        assert!(parse(&args("serve --port 70000"), 12).is_err());
+        assert!(parse(&args("serve 9000"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_dash() {
+        assert_eq!(parse(&args("dash"), 12), Ok(Command::Dash { timeout: 30 }));
+        assert_eq!(parse(&args("dash --timeout 5"), 12), Ok(Command::Dash { timeout: 5 }));
+        assert!(parse(&args("dash --timeout"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(parse(&args("watch 7"), 12), Ok(Command::Watch { day: 7, file: None }));
+        assert_eq!(parse(&args("watch 7 splitters.txt"), 12),
+                   Ok(Command::Watch { day: 7, file: Some("splitters.txt".to_string()) }));
+        assert!(parse(&args("watch"), 12).is_err());
+        assert!(parse(&args("watch 7 a.txt b.txt"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_record() {
+        assert_eq!(parse(&args("record"), 12), Ok(Command::Record { day: None }));
+        assert_eq!(parse(&args("record 10"), 12), Ok(Command::Record { day: Some(10) }));
+        assert!(parse(&args("record 10 11"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        assert_eq!(parse(&args("compare"), 12), Ok(Command::Compare { day: None, threshold: 25, against: None }));
+        assert_eq!(parse(&args("compare 10 --threshold 50 --against 713a53d"), 12),
+                   Ok(Command::Compare { day: Some(10), threshold: 50, against: Some("713a53d".to_string()) }));
+        assert!(parse(&args("compare --threshold -5"), 12).is_err());
+        assert!(parse(&args("compare 10 11"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_html() {
+        assert_eq!(parse(&args("html"), 12), Ok(Command::Html { file: "rs_aoc2025_report.html".to_string() }));
+        assert_eq!(parse(&args("html site/index.html"), 12), Ok(Command::Html { file: "site/index.html".to_string() }));
+        assert!(parse(&args("html a.html b.html"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_vis() {
+        assert_eq!(parse(&args("vis 4"), 12), Ok(Command::Vis { day: 4, file: None, style: Style::Ascii, frames: None }));
+        assert_eq!(parse(&args("vis 9 tiles.txt --svg --frames out"), 12),
+                   Ok(Command::Vis { day: 9, file: Some("tiles.txt".to_string()), style: Style::Svg, frames: Some("out".to_string()) }));
+        assert!(parse(&args("vis 9 --frames"), 12).is_err());
+        assert!(parse(&args("vis 9 a.txt b.txt"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_repl() {
+        assert_eq!(parse(&args("repl"), 12), Ok(Command::Repl));
+        assert!(parse(&args("repl 3"), 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        let repeats = |min_repeats, max_repeats, exact| { IdKind::Repeats { min_repeats, max_repeats, exact } };
+        assert_eq!(parse(&args("ids"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: repeats(2, usize::MAX, false), overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids ranges.txt --base 2 --repeats 3 --exact"), 12),
+                   Ok(Command::Ids { file: Some("ranges.txt".to_string()), base: 2, kind: repeats(3, 3, true), overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --repeats 2-4"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: repeats(2, 4, false), overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --repeats 3-"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: repeats(3, usize::MAX, false), overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --palindromes --base 16"), 12),
+                   Ok(Command::Ids { file: None, base: 16, kind: IdKind::Palindromes, overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --non-decreasing"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: IdKind::NonDecreasing, overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --pattern 1[0-9]*7"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: IdKind::Pattern("1[0-9]*7".to_string()), overlaps: Overlaps::AsGiven, breakdown: false }));
+        assert_eq!(parse(&args("ids --overlaps merge --breakdown"), 12),
+                   Ok(Command::Ids { file: None, base: 10, kind: repeats(2, usize::MAX, false), overlaps: Overlaps::Merge, breakdown: true }));
+        assert!(parse(&args("ids --overlaps sometimes"), 12).is_err());
+        assert!(parse(&args("ids --repeats two"), 12).is_err());
+        assert!(parse(&args("ids --repeats -4"), 12).is_err());
+        assert!(parse(&args("ids --base"), 12).is_err());
+        assert!(parse(&args("ids --pattern"), 12).is_err());
+        assert!(parse(&args("ids --palindromes --non-decreasing"), 12).is_err());
+        assert!(parse(&args("ids --palindromes --exact"), 12).is_err());
+        assert!(parse(&args("ids a.txt b.txt"), 12).is_err());
+    }
+
+    #[test]
+    fn test_take_overflow() {
+        let mut plain = args("9 --part 2");
+        assert_eq!(take_overflow(&mut plain), Ok(Policy::Checked));
+        assert_eq!(plain, args("9 --part 2"));
+
+        let mut big = args("9 --overflow big --part 2");
+        assert_eq!(take_overflow(&mut big), Ok(Policy::Big));
+        assert_eq!(big, args("9 --part 2"));
+
+        assert!(take_overflow(&mut args("9 --overflow huge")).is_err());
+        assert!(take_overflow(&mut args("9 --overflow")).is_err());
+    }
+}
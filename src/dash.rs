@@ -0,0 +1,404 @@
+// An interactive dashboard for running every day and keeping an eye on them.
+//
+// Each part runs in turn on a worker thread.  The table shows its status, answer and time, along
+// with the time from the last run (kept in TIMINGS_FILE.)  Keys:
+//
+//   up/down, k/j   select a day
+//   r              rerun the selected day       1, 2   rerun just that part
+//   a              rerun every day
+//   t              show or hide the selected day's trace notes
+//   q, esc         quit
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, event, execute, queue, terminal};
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+
+use rs_aoc2025::arith;
+use rs_aoc2025::day::{Answer, Day, SolveError};
+use rs_aoc2025::trace;
+
+// Part timings from the last run, one "day part micros" line per part.
+const TIMINGS_FILE: &str = "rs_aoc2025_timings.txt";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Status {
+    Pending,
+    Running,
+    Passed,
+    Failed,
+    Timeout,
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Pending => "pending",
+            Status::Running => "running",
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Timeout => "timeout",
+        }
+    }
+}
+
+// What's known about one part of one day.
+struct PartState {
+    status: Status,
+    answer: String,
+    micros: Option<u128>,
+    last_micros: Option<u128>,
+    trace: Vec<String>,
+}
+
+// A part that finished, sent back from its worker.  `id` says which run it was.
+struct Finished {
+    id: usize,
+    result: Result<Answer, SolveError>,
+    micros: u128,
+    trace: Vec<String>,
+}
+
+// The part running now: (run id, day, part, when it started.)
+type Running = (usize, usize, usize, Instant);
+
+struct Dashboard {
+    days: &'static [(&'static dyn Day, &'static str)],
+    expected: &'static [(Answer, Answer)],
+    parts: Vec<[PartState; 2]>,
+    queue: VecDeque<(usize, usize)>,
+    running: Option<Running>,
+    next_id: usize,
+    timeout: Duration,
+    selected: usize,
+    show_trace: bool,
+    sender: Sender<Finished>,
+    receiver: Receiver<Finished>,
+}
+
+// Timings saved by save_timings(), by (day, part).  Missing or unreadable files give none.
+fn load_timings(text: &str) -> HashMap<(usize, usize), u128> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().map(|f| { f.parse::<u128>().ok() });
+            let (Some(Some(day)), Some(Some(part)), Some(Some(micros))) = (fields.next(), fields.next(), fields.next()) else {
+                return None;
+            };
+            Some(((day as usize, part as usize), micros))
+        })
+        .collect()
+}
+
+// Time in the most readable unit.
+pub fn format_micros(micros: u128) -> String {
+    if micros < 1_000 {
+        format!("{micros} us")
+    }
+    else if micros < 1_000_000 {
+        format!("{:.1} ms", micros as f64 / 1e3)
+    }
+    else {
+        format!("{:.2} s", micros as f64 / 1e6)
+    }
+}
+
+impl Dashboard {
+    fn new(days: &'static [(&'static dyn Day, &'static str)], expected: &'static [(Answer, Answer)],
+           timeout: Duration, last_run: &HashMap<(usize, usize), u128>) -> Dashboard {
+        let part = |day_no: usize, part: usize| {
+            PartState {
+                status: Status::Pending,
+                answer: String::new(),
+                micros: None,
+                last_micros: last_run.get(&(day_no, part)).copied(),
+                trace: Vec::new(),
+            }
+        };
+        let parts = (1..=days.len()).map(|day_no| { [part(day_no, 1), part(day_no, 2)] }).collect();
+        let (sender, receiver) = channel();
+
+        Dashboard {
+            days, expected, parts,
+            queue: VecDeque::new(),
+            running: None,
+            next_id: 0,
+            timeout,
+            selected: 0,
+            show_trace: false,
+            sender, receiver,
+        }
+    }
+
+    // Queue a part to run, unless it's already waiting.
+    fn queue(&mut self, day_no: usize, part: usize) {
+        if !self.queue.contains(&(day_no, part)) {
+            self.parts[day_no-1][part-1].status = Status::Pending;
+            self.queue.push_back((day_no, part));
+        }
+    }
+
+    fn queue_all(&mut self) {
+        for day_no in 1..=self.days.len() {
+            self.queue(day_no, 1);
+            self.queue(day_no, 2);
+        }
+    }
+
+    // Start the next queued part on a worker thread, if nothing's running.
+    fn start_next(&mut self) {
+        if self.running.is_some() {
+            return;
+        }
+        let Some((day_no, part)) = self.queue.pop_front() else {
+            return;
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.running = Some((id, day_no, part, Instant::now()));
+        self.parts[day_no-1][part-1].status = Status::Running;
+
+        let (day, text) = self.days[day_no-1];
+        let sender = self.sender.clone();
+        let policy = arith::policy();
+        thread::spawn(move || {
+            arith::set_policy(policy);
+            let start = Instant::now();
+            let (result, trace) = trace::capture(|| {
+                catch_unwind(AssertUnwindSafe(|| {
+                    if part == 1 { day.try_part1(text) } else { day.try_part2(text) }
+                }))
+                .unwrap_or_else(|_| { Err(SolveError::Unsolvable(String::from("The solver panicked."))) })
+            });
+            let micros = start.elapsed().as_micros();
+
+            // (Nobody's listening if the dashboard has quit.)
+            let _ = sender.send(Finished { id, result, micros, trace });
+        });
+    }
+
+    // Record a finished part.  Results from runs that timed out are dropped.
+    fn finish(&mut self, finished: Finished) {
+        let Some((id, day_no, part, _)) = self.running else {
+            return;
+        };
+        if id != finished.id {
+            return;
+        }
+        self.running = None;
+
+        let expected = if part == 1 { &self.expected[day_no-1].0 } else { &self.expected[day_no-1].1 };
+        let state = &mut self.parts[day_no-1][part-1];
+        state.micros = Some(finished.micros);
+        state.trace = finished.trace;
+        match finished.result {
+            Ok(ans) => {
+                state.status = if ans == *expected { Status::Passed } else { Status::Failed };
+                state.answer = ans.to_string();
+            }
+            Err(e) => {
+                state.status = Status::Failed;
+                state.answer = format!("error: {e}");
+            }
+        }
+    }
+
+    // Give up on a part that's run too long.  Its thread can't be stopped, so it's left to finish
+    // in the background and its result ignored.
+    fn check_timeout(&mut self) {
+        if let Some((_, day_no, part, started)) = self.running
+            && started.elapsed() >= self.timeout {
+            let state = &mut self.parts[day_no-1][part-1];
+            state.status = Status::Timeout;
+            state.answer = format!("over {}", format_micros(self.timeout.as_micros()));
+            state.micros = None;
+            state.trace.clear();
+            self.running = None;
+        }
+    }
+
+    // Collect finished parts, time out a stuck one and start the next.
+    fn update(&mut self) {
+        while let Ok(finished) = self.receiver.try_recv() {
+            self.finish(finished);
+        }
+        self.check_timeout();
+        self.start_next();
+    }
+
+    // The screen, a line at a time, at most `height` lines.
+    fn render(&self, height: usize) -> Vec<String> {
+        let mut lines = vec![
+            String::from("Advent of Code 2025    r: rerun day  1/2: rerun part  a: rerun all  t: trace  q: quit"),
+            String::new(),
+            format!("  {:7} {:5} {:8} {:>20} {:>10} {:>10}", "", "Part", "Status", "Answer", "Time", "Last"),
+        ];
+
+        for (n, parts) in self.parts.iter().enumerate() {
+            for (p, state) in parts.iter().enumerate() {
+                let marker = if n == self.selected && p == 0 { ">" } else { " " };
+                let day = if p == 0 { format!("Day {:2}", n + 1) } else { String::new() };
+                let time = match (state.status, state.micros) {
+                    (Status::Running, _) => self.running
+                        .map_or(String::new(), |(_, _, _, started)| { format_micros(started.elapsed().as_micros()) }),
+                    (_, Some(micros)) => format_micros(micros),
+                    (_, None) => String::new(),
+                };
+                let last = state.last_micros.map_or(String::new(), format_micros);
+                lines.push(format!("{marker} {day:7} {:<5} {:8} {:>20} {time:>10} {last:>10}",
+                                   p + 1, state.status.name(), state.answer));
+            }
+        }
+
+        if self.show_trace {
+            lines.push(String::new());
+            for (p, state) in self.parts[self.selected].iter().enumerate() {
+                lines.push(format!("Day {} part {} trace ({} notes):", self.selected + 1, p + 1, state.trace.len()));
+                lines.extend(state.trace.iter().map(|note| { format!("  {note}") }));
+            }
+        }
+
+        lines.truncate(height);
+        lines
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        queue!(out, cursor::MoveTo(0, 0))?;
+        for line in self.render(height as usize) {
+            let line: String = line.chars().take(width as usize).collect();
+            queue!(out, terminal::Clear(terminal::ClearType::CurrentLine))?;
+            write!(out, "{line}\r\n")?;
+        }
+        queue!(out, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        out.flush()
+    }
+
+    // Handle a key.  Returns false to quit.
+    fn key(&mut self, code: KeyCode) -> bool {
+        let day_no = self.selected + 1;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(self.days.len() - 1),
+            KeyCode::Char('r') => {
+                self.queue(day_no, 1);
+                self.queue(day_no, 2);
+            }
+            KeyCode::Char('1') => self.queue(day_no, 1),
+            KeyCode::Char('2') => self.queue(day_no, 2),
+            KeyCode::Char('a') => self.queue_all(),
+            KeyCode::Char('t') => self.show_trace = !self.show_trace,
+            _ => {}
+        }
+
+        true
+    }
+
+    fn event_loop(&mut self, out: &mut impl Write) -> io::Result<()> {
+        loop {
+            self.update();
+            self.draw(out)?;
+            if event::poll(Duration::from_millis(100))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+
+    // Save the timings from this run, keeping the last ones for parts that didn't finish.
+    fn save_timings(&self) -> io::Result<()> {
+        let mut text = String::new();
+        for (n, parts) in self.parts.iter().enumerate() {
+            for (p, state) in parts.iter().enumerate() {
+                if let Some(micros) = state.micros.or(state.last_micros) {
+                    text.push_str(&format!("{} {} {micros}\n", n + 1, p + 1));
+                }
+            }
+        }
+
+        fs::write(TIMINGS_FILE, text)
+    }
+}
+
+// Run the dashboard until the user quits.  Parts running longer than `timeout` are given up on.
+pub fn run(days: &'static [(&'static dyn Day, &'static str)], expected: &'static [(Answer, Answer)],
+           timeout: Duration) -> io::Result<()> {
+    let last_run = load_timings(&fs::read_to_string(TIMINGS_FILE).unwrap_or_default());
+    let mut dash = Dashboard::new(days, expected, timeout, &last_run);
+    dash.queue_all();
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = dash.event_loop(&mut out);
+
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result.and_then(|_| { dash.save_timings() })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use rs_aoc2025::day::{Answer, SolveError};
+
+    use crate::{DAYS, EXPECTED};
+    use crate::dash::{format_micros, load_timings, Dashboard, Finished, Status};
+
+    #[test]
+    fn test_load_timings() {
+        let timings = load_timings("1 1 250\n1 2 1200\nnonsense\n12 1 3\n");
+        assert_eq!(timings, HashMap::from([((1, 1), 250), ((1, 2), 1200), ((12, 1), 3)]));
+    }
+
+    #[test]
+    fn test_format_micros() {
+        assert_eq!(format_micros(999), "999 us");
+        assert_eq!(format_micros(12_345), "12.3 ms");
+        assert_eq!(format_micros(2_500_000), "2.50 s");
+    }
+
+    #[test]
+    // Answers are checked against the expected ones, and results from a timed out run are dropped.
+    fn test_finish() {
+        let last_run = HashMap::from([((1, 1), 250)]);
+        let mut dash = Dashboard::new(&DAYS, &EXPECTED, Duration::ZERO, &last_run);
+        dash.queue_all();
+        assert_eq!(dash.queue.len(), 24);
+
+        let run = |dash: &mut Dashboard, result| {
+            let id = dash.next_id;
+            dash.running = Some((id, 1, 1, std::time::Instant::now()));
+            dash.finish(Finished { id, result, micros: 100, trace: vec![String::from("note")] });
+            (dash.parts[0][0].status, dash.parts[0][0].answer.clone())
+        };
+        assert_eq!(run(&mut dash, Ok(Answer::Numeric(1105))), (Status::Passed, String::from("1105")));
+        assert_eq!(run(&mut dash, Ok(Answer::Numeric(1))), (Status::Failed, String::from("1")));
+        assert_eq!(run(&mut dash, Err(SolveError::parse(3, "Bad move"))),
+                   (Status::Failed, String::from("error: line 3: Bad move")));
+
+        let lines = dash.render(100);
+        assert!(lines[3].starts_with("> Day  1  1     failed   error: line 3: Bad move"));
+        assert!(lines[3].ends_with("100 us     250 us"));
+
+        // With no time allowed, the run times out, and its result is ignored.
+        dash.running = Some((99, 1, 2, std::time::Instant::now()));
+        dash.check_timeout();
+        assert_eq!(dash.parts[0][1].status, Status::Timeout);
+        dash.finish(Finished { id: 99, result: Ok(Answer::Numeric(6599)), micros: 1, trace: Vec::new() });
+        assert_eq!(dash.parts[0][1].status, Status::Timeout);
+    }
+}
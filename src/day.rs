@@ -1,18 +1,191 @@
+use std::fmt;
+use std::io::BufRead;
+
+use num_bigint::BigUint;
+
+use crate::rng::Rng;
+use crate::vis::Style;
+
 #[allow(dead_code)]
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Answer {
     None,
     Numeric(usize),
     String(String),
+
+    // Too big for Numeric.  Only when the overflow policy allows wider arithmetic.
+    Big(BigUint),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::None => f.pad("-"),
+            Answer::Numeric(n) => f.pad(&n.to_string()),
+            Answer::String(s) => f.pad(s),
+            Answer::Big(n) => f.pad(&n.to_string()),
+        }
+    }
+}
+
+// Why a day couldn't produce an answer.
+#[derive(PartialEq, Eq, Debug)]
+pub enum SolveError {
+    // The input doesn't follow the puzzle's format.  Lines are numbered from 1.
+    Parse { line: usize, message: String },
+
+    // The input parsed, but has no answer.
+    Unsolvable(String),
+
+    // An intermediate value didn't fit.  Names the day and the operation.
+    Overflow { day: usize, operation: &'static str },
+
+    // The strategy isn't registered for that part.
+    NoStrategy { part: usize, strategy: String },
+
+    // Reading streamed input failed.
+    Read(String),
+}
+
+impl SolveError {
+    // Shorthand for a parse error on a line (numbered from 1.)
+    pub fn parse(line: usize, message: impl Into<String>) -> SolveError {
+        SolveError::Parse { line, message: message.into() }
+    }
+
+    pub fn overflow(day: usize, operation: &'static str) -> SolveError {
+        SolveError::Overflow { day, operation }
+    }
+
+    // The line a parse error is on.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            SolveError::Parse { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            SolveError::Unsolvable(message) => write!(f, "{message}"),
+            SolveError::Overflow { day, operation } => write!(f, "day {day}: overflow in {operation}"),
+            SolveError::NoStrategy { part, strategy } => write!(f, "part {part} has no strategy '{strategy}'"),
+            SolveError::Read(message) => write!(f, "read error: {message}"),
+        }
+    }
 }
 
+// Name of the strategy for days that only have one way to solve a part.
+pub const DEFAULT_STRATEGY: &str = "default";
+
 pub trait Day: Sync {
 
-    fn part1(&self, _input: &str) -> Answer {
-        Answer::None
+    fn try_part1(&self, _input: &str) -> Result<Answer, SolveError> {
+        Ok(Answer::None)
+    }
+
+    fn try_part2(&self, _input: &str) -> Result<Answer, SolveError> {
+        Ok(Answer::None)
     }
 
-    fn part2(&self, _input: &str) -> Answer {
-        Answer::None
+    // Like try_part1(), but panics if there is no answer.
+    fn part1(&self, input: &str) -> Answer {
+        self.try_part1(input).unwrap_or_else(|e| { panic!("Part 1: {e}") })
     }
-}
\ No newline at end of file
+
+    // Like try_part2(), but panics if there is no answer.
+    fn part2(&self, input: &str) -> Answer {
+        self.try_part2(input).unwrap_or_else(|e| { panic!("Part 2: {e}") })
+    }
+
+    // Names of the solver strategies registered for a part.
+    // The first one is the strategy part1() and part2() use, and solve_with() also takes
+    // DEFAULT_STRATEGY for it, whatever it's called.
+    fn strategies(&self, _part: usize) -> Vec<&'static str> {
+        vec![DEFAULT_STRATEGY]
+    }
+
+    // Solve a part with a named strategy.
+    fn solve_with(&self, part: usize, strategy: &str, input: &str) -> Result<Answer, SolveError> {
+        match (part, strategy) {
+            (1, DEFAULT_STRATEGY) => self.try_part1(input),
+            (2, DEFAULT_STRATEGY) => self.try_part2(input),
+            _ => Err(SolveError::NoStrategy { part, strategy: strategy.to_string() }),
+        }
+    }
+
+    // Solve a part reading the input from `reader` as it goes, so memory stays bounded however
+    // large the input is.  None if the day can't solve that part this way.
+    fn solve_stream(&self, _part: usize, _reader: &mut dyn BufRead) -> Option<Result<Answer, SolveError>> {
+        None
+    }
+
+    // Check an input against the assumptions this day's solvers make about it, without solving.
+    // Every violation found, in line order.  None if the day has no checks.
+    fn lint(&self, _input: &str) -> Option<Vec<SolveError>> {
+        None
+    }
+
+    // Random input in the format this day reads, roughly `size` records long.
+    // None if the day has no generator.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+
+    // What the solvers build from an input (the parsed input and the structures derived from
+    // it), described for a person to read.  None if the day has nothing to show.
+    fn inspect(&self, _input: &str) -> Option<Result<String, SolveError>> {
+        None
+    }
+
+    // Pictures of how the day works through an input, as frames drawn in `style` (see vis.rs.)
+    // None if the day has nothing to draw.
+    fn visualize(&self, _input: &str, _style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        None
+    }
+}
+
+// Run every registered strategy for both parts of a day.
+// This is the whole parse-and-solve path; the fuzz targets drive it with arbitrary input.
+pub fn solve_all(day: &dyn Day, input: &str) -> Vec<Result<Answer, SolveError>> {
+    (1..=2)
+        .flat_map(|part| {
+            day.strategies(part).into_iter().map(move |strategy| { day.solve_with(part, strategy, input) })
+        })
+        .collect()
+}
+
+// Call `f` with each record read from `reader`, where records end with `delimiter` or the end of
+// the input, along with the line number (from 1) the record starts on.  Only one record is held
+// at a time.
+pub fn for_each_record(reader: &mut dyn BufRead, delimiter: u8,
+                       mut f: impl FnMut(usize, &str) -> Result<(), SolveError>) -> Result<(), SolveError> {
+    let mut buf = Vec::new();
+    let mut line_no = 1;
+
+    loop {
+        buf.clear();
+        let n = reader.read_until(delimiter, &mut buf).map_err(|e| { SolveError::Read(e.to_string()) })?;
+        if n == 0 {
+            return Ok(());
+        }
+        let newlines = buf.iter().filter(|b| { **b == b'\n' }).count();
+
+        if buf.last() == Some(&delimiter) {
+            buf.pop();
+        }
+        let record = std::str::from_utf8(&buf).map_err(|_| { SolveError::parse(line_no, "Not UTF-8.") })?;
+        f(line_no, record)?;
+
+        line_no += newlines;
+    }
+}
+
+// Call `f` with each line read from `reader`, like str::lines().
+pub fn for_each_line(reader: &mut dyn BufRead,
+                     mut f: impl FnMut(usize, &str) -> Result<(), SolveError>) -> Result<(), SolveError> {
+    for_each_record(reader, b'\n', |line_no, line| { f(line_no, line.strip_suffix('\r').unwrap_or(line)) })
+}
@@ -1,97 +1,832 @@
-use crate::day::{Day, Answer};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::Range;
+
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError, for_each_line};
+use crate::rng::Rng;
+
+const DAY: usize = 1;
+
+// One line of a dial program.  Besides the puzzle's moves ("L68", "R48"), a program can set the
+// dial to a position ("set 50") without clicking through the ones between, and repeat a block of
+// lines ("repeat 1000" up to a matching "end".)  Blocks nest.  Anything after a '#' is a comment,
+// and blank lines are skipped.
+//
+// A program can also work a combination lock: several dials, each declared as
+// "dial <id> <size> <start>" before anything else.  A move or set tagged with a dial's id, as in
+// "a:L68", works just that dial; an untagged one works them all.  With no dials declared, there's
+// one, the day's usual dial.
+#[derive(Clone, PartialEq, Debug)]
+enum Instruction {
+    // The dial by number (None for all of them), and the move or position.
+    Turn(Option<usize>, isize),
+    Set(Option<usize>, usize),
+    Repeat(usize, Vec<Instruction>),
+}
+
+// Builds instructions from a program a line at a time, holding only the blocks still open.
+struct Parser {
+    // Each open block's line number, repeat count and instructions so far, innermost last.
+    open: Vec<(usize, usize, Vec<Instruction>)>,
+    // The dials declared, and whether any other line has been seen yet.
+    dials: Vec<(String, Dial)>,
+    started: bool,
+}
+
+impl Parser {
+    fn new() -> Parser {
+        Parser { open: Vec::new(), dials: Vec::new(), started: false }
+    }
+
+    // Parse a line.  Returns an instruction once it's complete and outside any block.
+    fn line(&mut self, line_no: usize, line: &str) -> Result<Option<Instruction>, SolveError> {
+        let code = line.split('#').next().unwrap_or("").trim();
+        if code.is_empty() {
+            return Ok(None);
+        }
+        if let Some(declaration) = code.strip_prefix("dial ") {
+            if self.started {
+                return Err(SolveError::parse(line_no, "Dials must be declared before the moves"));
+            }
+            let dial = self.parse_dial(line_no, declaration)?;
+            self.dials.push(dial);
+            return Ok(None);
+        }
+        self.started = true;
+
+        let (dial, code) = match code.split_once(':') {
+            Some((id, code)) => {
+                let id = id.trim();
+                let dial = self.dials.iter().position(|(d, _)| { d == id })
+                    .ok_or_else(|| { SolveError::parse(line_no, format!("No dial '{id}'")) })?;
+                (Some(dial), code.trim())
+            }
+            None => (None, code),
+        };
+        let words: Vec<&str> = code.split_whitespace().collect();
+        let instruction = match words.as_slice() {
+            ["repeat", times] if dial.is_none() => {
+                let times = times.parse::<usize>()
+                    .map_err(|_| { SolveError::parse(line_no, format!("Bad repeat '{code}'")) })?;
+                self.open.push((line_no, times, Vec::new()));
+                return Ok(None);
+            }
+            ["end"] if dial.is_none() => {
+                let (_, times, block) = self.open.pop()
+                    .ok_or_else(|| { SolveError::parse(line_no, "'end' without a 'repeat'") })?;
+                Instruction::Repeat(times, block)
+            }
+            ["set", position] => {
+                let position = position.parse::<usize>()
+                    .map_err(|_| { SolveError::parse(line_no, format!("Bad set '{code}'")) })?;
+                Instruction::Set(dial, position)
+            }
+            _ => Instruction::Turn(dial, Input::parse_move(line_no, code)?),
+        };
+
+        match self.open.last_mut() {
+            Some((_, _, block)) => {
+                block.push(instruction);
+                Ok(None)
+            }
+            None => Ok(Some(instruction)),
+        }
+    }
+
+    // Parse the "<id> <size> <start>" of a dial declaration.
+    fn parse_dial(&self, line_no: usize, declaration: &str) -> Result<(String, Dial), SolveError> {
+        let bad_dial = || { SolveError::parse(line_no, format!("Bad dial 'dial {declaration}'")) };
+
+        let fields: Vec<&str> = declaration.split_whitespace().collect();
+        let [id, size, start] = fields[..] else {
+            return Err(bad_dial());
+        };
+        if id.contains(':') {
+            return Err(bad_dial());
+        }
+        if self.dials.iter().any(|(d, _)| { d == id }) {
+            return Err(SolveError::parse(line_no, format!("Dial '{id}' is declared twice")));
+        }
+        let size = size.parse::<usize>().map_err(|_| { bad_dial() })?;
+        let start = start.parse::<usize>().map_err(|_| { bad_dial() })?;
+        let dial = Dial::new(size, start).map_err(|e| { SolveError::parse(line_no, e.to_string()) })?;
+
+        Ok((id.to_string(), dial))
+    }
+
+    // Check that the program ended outside any block.
+    fn finish(&self) -> Result<(), SolveError> {
+        match self.open.last() {
+            Some((line_no, _, _)) => Err(SolveError::parse(*line_no, "'repeat' without an 'end'")),
+            None => Ok(()),
+        }
+    }
+}
 
 // A representation of the puzzle inputs.
 struct Input {
-    moves: Vec<isize>,
+    dials: Vec<(String, Dial)>,
+    program: Vec<Instruction>,
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
-        let mut moves = Vec::new();
-        for line in text.lines() {
+        let mut parser = Parser::new();
+        let mut program = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
             // Process a line
-            let thismove = match &line[0..1] {
-                "L" => -line[1..].parse::<isize>().unwrap(),
-                "R" => line[1..].parse::<isize>().unwrap(),
-                _ => panic!("Bad move in input file."),
+            if let Some(instruction) = parser.line(line_no + 1, line)? {
+                program.push(instruction);
+            }
+        }
+        parser.finish()?;
+
+        Ok(Input { dials: parser.dials, program })
+    }
+
+    // The moves the program makes, one by one, made as they're asked for so that blocks aren't
+    // written out.  For the things that work a move at a time; programs that set the dial, or
+    // work a lock, don't have a list of moves.
+    fn moves(self) -> Result<Moves, SolveError> {
+        fn only_moves(program: &[Instruction]) -> bool {
+            program.iter().all(|instruction| {
+                match instruction {
+                    Instruction::Turn(_, _) => true,
+                    Instruction::Set(_, _) => false,
+                    Instruction::Repeat(_, block) => only_moves(block),
+                }
+            })
+        }
+
+        if !self.dials.is_empty() {
+            return Err(SolveError::Unsolvable(String::from("Only programs for one dial can be taken a move at a time.")));
+        }
+        if !only_moves(&self.program) {
+            return Err(SolveError::Unsolvable(String::from("Only programs of moves can be taken a move at a time.")));
+        }
+
+        Ok(Moves { program: self.program, stack: vec![(0, 0, 1)] })
+    }
+
+    // Parse one move, e.g. "L68" is -68.  Lines are numbered from 1.
+    fn parse_move(line_no: usize, line: &str) -> Result<isize, SolveError> {
+        let bad_move = || { SolveError::parse(line_no, format!("Bad move '{line}'")) };
+
+        let (dir, clicks) = line.split_at_checked(1).ok_or_else(bad_move)?;
+        if clicks.is_empty() || !clicks.chars().all(|c| { c.is_ascii_digit() }) {
+            return Err(bad_move());
+        }
+        let clicks = clicks.parse::<isize>().map_err(|_| { bad_move() })?;
+        match dir {
+            "L" => Ok(-clicks),
+            "R" => Ok(clicks),
+            _ => Err(bad_move()),
+        }
+    }
+
+    // Random input in the format read() accepts: `size` moves of 1 to 999 clicks.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..size {
+            let dir = if rng.chance(1, 2) { 'L' } else { 'R' };
+            text.push_str(&format!("{dir}{}\n", rng.range(1, 999)));
+        }
+
+        text
+    }
+}
+
+// The moves of a program of moves, in order, without writing its blocks out.
+pub struct Moves {
+    program: Vec<Instruction>,
+    // For each block being run, innermost last: where its repeat is in the block around it, the
+    // next instruction, and how many runs of it are left.  The program itself is run once.
+    stack: Vec<(usize, usize, usize)>,
+}
+
+impl Moves {
+    // The innermost block being run.
+    fn block(&self) -> &[Instruction] {
+        let mut block = self.program.as_slice();
+        for (at, _, _) in &self.stack[1..] {
+            if let Instruction::Repeat(_, inner) = &block[*at] {
+                block = inner;
+            }
+        }
+
+        block
+    }
+}
+
+// Whether running a program makes any moves at all, so a block can be skipped if not.
+fn makes_moves(program: &[Instruction]) -> bool {
+    program.iter().any(|instruction| {
+        match instruction {
+            Instruction::Turn(_, _) => true,
+            Instruction::Set(_, _) => false,
+            Instruction::Repeat(times, block) => *times > 0 && makes_moves(block),
+        }
+    })
+}
+
+impl Iterator for Moves {
+    type Item = isize;
+
+    fn next(&mut self) -> Option<isize> {
+        loop {
+            let &(_, next, _) = self.stack.last()?;
+            let (m, enter) = match self.block().get(next) {
+                Some(Instruction::Turn(_, m)) => (Some(*m), None),
+                Some(Instruction::Repeat(times, block)) if *times > 0 && makes_moves(block) => (None, Some(*times)),
+                Some(_) => (None, None),
+                None => {
+                    // The end of a run: go round again, or leave the block.
+                    let (_, next, left) = self.stack.last_mut()?;
+                    *left -= 1;
+                    if *left == 0 {
+                        self.stack.pop();
+                    }
+                    else {
+                        *next = 0;
+                    }
+                    continue;
+                }
             };
 
-            moves.push(thismove);
+            if let Some((_, next, _)) = self.stack.last_mut() {
+                *next += 1;
+            }
+            if let Some(times) = enter {
+                self.stack.push((next, 0, times));
+            }
+            if m.is_some() {
+                return m;
+            }
+        }
+    }
+}
+
+// A dial with `size` positions, 0 to size - 1, turned a click at a time: left (down) for
+// negative moves, right (up) for positive ones, wrapping around.
+#[derive(Clone, Debug)]
+pub struct Dial {
+    size: usize,
+    position: usize,
+}
+
+// What one move did: where the dial started and stopped, how many clicks reached zero
+// (including the last, if it stopped there), and whether it stopped on zero.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Turn {
+    pub from: usize,
+    pub to: usize,
+    pub passes: usize,
+    pub landed: bool,
+}
+
+// The turns a dial makes for a run of moves, one per move.
+pub struct Trajectory<I> {
+    dial: Dial,
+    moves: I,
+}
+
+impl<I: Iterator<Item = isize>> Iterator for Trajectory<I> {
+    type Item = Turn;
+
+    fn next(&mut self) -> Option<Turn> {
+        self.moves.next().map(|m| { self.dial.turn(m) })
+    }
+}
+
+impl Dial {
+    pub fn new(size: usize, start: usize) -> Result<Dial, SolveError> {
+        if start >= size {
+            return Err(SolveError::Unsolvable(format!("Can't start at {start} on a dial of size {size}.")));
         }
 
-        Input { moves }
+        Ok(Dial { size, position: start })
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // Make a move, working out the zeros passed without going a click at a time.
+    pub fn turn(&mut self, m: isize) -> Turn {
+        let from = self.position;
+        let size = self.size as i128;
+        let clicks = m.unsigned_abs() as i128;
+
+        // Clicks from here to zero in the direction of the move (a whole turn if already there.)
+        let to_zero = if m >= 0 { size - self.position as i128 } else { self.position as i128 };
+        let to_zero = if to_zero == 0 { size } else { to_zero };
+        let passes = if clicks < to_zero { 0 } else { (clicks - to_zero) / size + 1 };
+
+        self.position = (self.position as i128 + m as i128).rem_euclid(size) as usize;
+
+        Turn { from, to: self.position, passes: passes as usize, landed: self.position == 0 }
+    }
+
+    // Put the dial straight at a position, clicking through none of the others.
+    pub fn set(&mut self, position: usize) -> Result<(), SolveError> {
+        if position >= self.size {
+            return Err(SolveError::Unsolvable(format!("Can't set {position} on a dial of size {}.", self.size)));
+        }
+        self.position = position;
+
+        Ok(())
+    }
+
+    // Make each of the moves in turn, lazily.
+    pub fn run<I: IntoIterator<Item = isize>>(self, moves: I) -> Trajectory<I::IntoIter> {
+        Trajectory { dial: self, moves: moves.into_iter() }
+    }
+}
+
+// Zeros seen over a run of moves: moves that stopped on zero (part 1), and every click that
+// reached zero (part 2.)
+#[derive(Clone, PartialEq, Debug)]
+pub struct Zeros<T> {
+    pub landings: T,
+    pub passes: T,
+}
+
+impl<T: Arith> Zeros<T> {
+    pub fn new() -> Zeros<T> {
+        Zeros { landings: T::from_usize(0), passes: T::from_usize(0) }
+    }
+
+    pub fn add(&mut self, turn: Turn) -> Result<(), SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "zero count") };
+
+        self.passes = self.passes.checked_add(&T::from_usize(turn.passes)).ok_or_else(overflow)?;
+        if turn.landed {
+            self.landings = self.landings.checked_add(&T::from_usize(1)).ok_or_else(overflow)?;
+        }
+
+        Ok(())
+    }
+
+    // Add in another count's zeros.
+    pub fn merge(&mut self, other: &Zeros<T>) -> Result<(), SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "zero count") };
+
+        self.landings = self.landings.checked_add(&other.landings).ok_or_else(overflow)?;
+        self.passes = self.passes.checked_add(&other.passes).ok_or_else(overflow)?;
+
+        Ok(())
+    }
+
+    // The zeros from this many runs of the same moves.
+    fn times(&self, n: usize) -> Result<Zeros<T>, SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "zero count") };
+        let n = T::from_usize(n);
+
+        Ok(Zeros {
+            landings: self.landings.checked_mul(&n).ok_or_else(overflow)?,
+            passes: self.passes.checked_mul(&n).ok_or_else(overflow)?,
+        })
+    }
+
+    // The count a part asks for.
+    fn answer(self, part: usize) -> Answer {
+        if part == 1 { self.landings.into_answer() } else { self.passes.into_answer() }
+    }
+}
+
+// Zeros over a lock's moves: for each dial, for all of them together, and the first move (by
+// number, from 1) after which every dial read zero at once.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LockZeros {
+    pub dials: Vec<(String, Zeros<u128>)>,
+    pub combined: Zeros<u128>,
+    pub all_zero: Option<u128>,
+}
+
+// The same, counted as a program runs, with the moves made so far.
+#[derive(Clone, Debug)]
+struct Count<T> {
+    dials: Vec<Zeros<T>>,
+    combined: Zeros<T>,
+    moves: T,
+    all_zero: Option<T>,
+}
+
+impl<T: Arith> Count<T> {
+    fn new(dials: usize) -> Count<T> {
+        Count { dials: vec![Zeros::new(); dials], combined: Zeros::new(), moves: T::from_usize(0), all_zero: None }
+    }
+
+    // Make a move, on one dial or all of them.
+    fn turn(&mut self, dials: &mut [Dial], dial: Option<usize>, m: isize) -> Result<(), SolveError> {
+        for d in chosen(dial, dials.len()) {
+            let turn = dials[d].turn(m);
+            self.dials[d].add(turn)?;
+            self.combined.add(turn)?;
+        }
+        self.moves = self.moves.checked_add(&T::from_usize(1)).ok_or_else(|| { SolveError::overflow(DAY, "move count") })?;
+        if self.all_zero.is_none() && dials.iter().all(|d| { d.position() == 0 }) {
+            self.all_zero = Some(self.moves.clone());
+        }
+
+        Ok(())
+    }
+
+    // Add in the count of the moves that came next.
+    fn merge(&mut self, other: &Count<T>) -> Result<(), SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "move count") };
+
+        if self.all_zero.is_none() && let Some(n) = &other.all_zero {
+            self.all_zero = Some(self.moves.checked_add(n).ok_or_else(overflow)?);
+        }
+        for (zeros, other) in self.dials.iter_mut().zip(&other.dials) {
+            zeros.merge(other)?;
+        }
+        self.combined.merge(&other.combined)?;
+        self.moves = self.moves.checked_add(&other.moves).ok_or_else(overflow)?;
+
+        Ok(())
+    }
+
+    // The count from this many runs of the same moves.  The dials first all read zero in the
+    // first run, if ever.
+    fn times(&self, n: usize) -> Result<Count<T>, SolveError> {
+        Ok(Count {
+            dials: self.dials.iter().map(|zeros| { zeros.times(n) }).collect::<Result<_, _>>()?,
+            combined: self.combined.times(n)?,
+            moves: self.moves.checked_mul(&T::from_usize(n)).ok_or_else(|| { SolveError::overflow(DAY, "move count") })?,
+            all_zero: if n == 0 { None } else { self.all_zero.clone() },
+        })
+    }
+}
+
+// The dials an instruction works: the one it's tagged with, or all of them.
+fn chosen(dial: Option<usize>, dials: usize) -> Range<usize> {
+    match dial {
+        Some(d) => d..d + 1,
+        None => 0..dials,
     }
 }
 
 pub struct Day1 {
+    size: usize,
+    start: usize,
 }
 
 // Day1
 impl Day1 {
     pub const fn new() -> Self {
-        Self { }
+        Self::with_dial(100, 50)
     }
-}
 
-impl Day for Day1 {
+    // A dial with another size or start position than the puzzle's.
+    pub const fn with_dial(size: usize, start: usize) -> Self {
+        Self { size, start }
+    }
 
-    // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    // What each move in an input does to the dial, in order.
+    pub fn events(&self, text: &str) -> Result<Trajectory<Moves>, SolveError> {
+        let moves = Input::read(text)?.moves()?;
 
-        let mut position = 50;
-        let mut zeros = 0;
-        for m in input.moves {
-            position = (position + m) % 100;
-            if position == 0 {
-                zeros += 1;
+        Ok(Dial::new(self.size, self.start)?.run(moves))
+    }
+
+    // The zeros from every start position at once, indexed by start.
+    pub fn zeros_by_start(&self, text: &str) -> Result<Vec<Zeros<u128>>, SolveError> {
+        let input = Input::read(text)?;
+        Dial::new(self.size, self.start)?;
+        if !input.dials.is_empty() {
+            return Err(SolveError::Unsolvable(String::from("Zeros by start are for one dial, not a lock.")));
+        }
+
+        Ok(self.block_by_start(&input.program)?.0)
+    }
+
+    // The zeros by start for a run of instructions, and how far they turn the dial (mod size.)
+    //
+    // After k moves the dial is at (start + P) mod size, where P is the sum of the moves so far, so
+    // each move lands on zero from just one start.  The zeros a move passes going from a to b are
+    // floor((start + b) / size) - floor((start + a) / size) (shifted down one click going left),
+    // and each floor is a constant plus a step of one at start = size - x mod size.  So the passes
+    // for all starts are a total plus a running sum of steps.
+    //
+    // A block starting P clicks on counts what it would from a start P further on, so its zeros by
+    // start (see repeat_by_start) are added in shifted along by P.
+    fn block_by_start(&self, program: &[Instruction]) -> Result<(Vec<Zeros<u128>>, usize), SolveError> {
+        let size = self.size as i128;
+
+        let mut landings = vec![0u128; self.size];
+        let mut total: i128 = 0;
+        let mut steps = vec![0i128; self.size];
+        let mut add_floor = |x: i128, sign: i128| {
+            total += sign * x.div_euclid(size);
+            let r = x.rem_euclid(size);
+            if r > 0 {
+                steps[(size - r) as usize] += sign;
+            }
+        };
+        let mut blocks = vec![Zeros::new(); self.size];
+
+        // Only P mod size matters, to the floors as much as the rest.
+        let mut at: i128 = 0;
+        for instruction in program {
+            match instruction {
+                Instruction::Turn(_, m) => {
+                    let next = at + *m as i128;
+                    if *m >= 0 {
+                        add_floor(next, 1);
+                        add_floor(at, -1);
+                    }
+                    else {
+                        add_floor(at - 1, 1);
+                        add_floor(next - 1, -1);
+                    }
+                    landings[(-next).rem_euclid(size) as usize] += 1;
+                    at = next.rem_euclid(size);
+                }
+                Instruction::Set(_, _) => {
+                    return Err(SolveError::Unsolvable(String::from("Only programs of moves can be taken a move at a time.")));
+                }
+                Instruction::Repeat(times, block) => {
+                    let (once, turned) = self.block_by_start(block)?;
+                    let repeated = repeat_by_start(&once, turned, *times)?;
+                    for (start, zeros) in blocks.iter_mut().enumerate() {
+                        zeros.merge(&repeated[(start + at as usize) % self.size])?;
+                    }
+                    at = (at + (*times as i128 % size) * turned as i128) % size;
+                }
             }
         }
 
-        Answer::Numeric(zeros)
+        let mut passes = total;
+        let by_start = landings.into_iter().zip(steps).zip(blocks)
+            .map(|((landings, step), blocks)| {
+                passes += step;
+                let mut zeros = Zeros { landings, passes: passes as u128 };
+                zeros.merge(&blocks)?;
+                Ok(zeros)
+            })
+            .collect::<Result<_, SolveError>>()?;
+
+        Ok((by_start, at as usize))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    // Turn the dials of a lock (see Instruction) and count their zeros.
+    pub fn lock_zeros(&self, text: &str) -> Result<LockZeros, SolveError> {
+        let input = Input::read(text)?;
+        let count = self.count_zeros::<u128>(&input)?;
 
-        let mut position = 50;
-        let mut zeros: usize = 0;
-        for m in input.moves {
-            let full_rotations = (m / 100).abs() as usize;
-            let partial_rotation = m % 100;
+        let ids = self.dials(&input.dials)?.into_iter().map(|(id, _)| { id });
+        Ok(LockZeros { dials: ids.zip(count.dials).collect(), combined: count.combined, all_zero: count.all_zero })
+    }
 
-            // count all those full rotation crossings.
-            zeros += full_rotations;
-            let mut new_position = position + partial_rotation;
+    // The dials a program works: the ones it declares, or else the usual one.
+    fn dials(&self, declared: &[(String, Dial)]) -> Result<Vec<(String, Dial)>, SolveError> {
+        if declared.is_empty() {
+            Ok(vec![(String::from("dial"), Dial::new(self.size, self.start)?)])
+        }
+        else {
+            Ok(declared.to_vec())
+        }
+    }
 
-            if new_position == 0 {
-                // landed on zero.
-                zeros += 1;
-            } else if new_position > 99 {
-                // crossed in positive dir
-                zeros += 1;
-                new_position -= 100;
-            } else if new_position < 0 {
-                // crossed in negative dir
-                if position != 0 {
-                     zeros += 1;
+    // Count zeros over a whole program.
+    fn count_zeros<T: Arith>(&self, input: &Input) -> Result<Count<T>, SolveError> {
+        let mut dials: Vec<Dial> = self.dials(&input.dials)?.into_iter().map(|(_, dial)| { dial }).collect();
+        let mut count = Count::new(dials.len());
+        self.run(&input.program, &mut dials, &mut count)?;
+
+        Ok(count)
+    }
+
+    // Count zeros, reading a program from a stream.  Instructions are run as soon as they're
+    // complete, so only the blocks still open are held.  The dials are all declared by the
+    // first one.
+    fn stream_zeros<T: Arith>(&self, reader: &mut dyn BufRead) -> Result<Count<T>, SolveError> {
+        let mut lock: Option<(Vec<Dial>, Count<T>)> = None;
+        let mut parser = Parser::new();
+        for_each_line(reader, |line_no, line| {
+            let Some(instruction) = parser.line(line_no, line)? else {
+                return Ok(());
+            };
+            if lock.is_none() {
+                let dials: Vec<Dial> = self.dials(&parser.dials)?.into_iter().map(|(_, dial)| { dial }).collect();
+                let count = Count::new(dials.len());
+                lock = Some((dials, count));
+            }
+            match &mut lock {
+                Some((dials, count)) => self.run(std::slice::from_ref(&instruction), dials, count),
+                None => Ok(()),
+            }
+        })?;
+        parser.finish()?;
+
+        match lock {
+            Some((_, count)) => Ok(count),
+            None => Ok(Count::new(self.dials(&parser.dials)?.len())),
+        }
+    }
+
+    // Run instructions on the dials, adding up the zeros.  A set clicks past nothing, so it
+    // neither lands on zero nor passes it.
+    fn run<T: Arith>(&self, program: &[Instruction], dials: &mut [Dial], count: &mut Count<T>) -> Result<(), SolveError> {
+        for instruction in program {
+            match instruction {
+                Instruction::Turn(dial, m) => count.turn(dials, *dial, *m)?,
+                Instruction::Set(dial, position) => {
+                    for d in chosen(*dial, dials.len()) {
+                        dials[d].set(*position)?;
+                    }
                 }
-                new_position += 100
+                Instruction::Repeat(times, block) => self.repeat(block, *times, dials, count)?,
             }
+        }
 
-            position = new_position;
+        Ok(())
+    }
+
+    // Run a block `times` times.  Only the dials' positions carry from one run of the block to
+    // the next, so once a run starts where an earlier one did, the runs between come round again
+    // and again.  Count those once and multiply, so no more runs are made than there are ways
+    // to set the dials.
+    fn repeat<T: Arith>(&self, block: &[Instruction], times: usize, dials: &mut [Dial], count: &mut Count<T>)
+        -> Result<(), SolveError> {
+        let mut seen: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut starts: Vec<Vec<usize>> = Vec::new();
+        let mut runs: Vec<Count<T>> = Vec::new();
+        while runs.len() < times {
+            let positions: Vec<usize> = dials.iter().map(|d| { d.position() }).collect();
+            if let Some(&first) = seen.get(&positions) {
+                let left = times - runs.len();
+                let cycle = &runs[first..];
+                let mut once = Count::new(dials.len());
+                for run in cycle {
+                    once.merge(run)?;
+                }
+                count.merge(&once.times(left / cycle.len())?)?;
+                for run in &cycle[..left % cycle.len()] {
+                    count.merge(run)?;
+                }
+                for (dial, position) in dials.iter_mut().zip(&starts[first + left % cycle.len()]) {
+                    dial.set(*position)?;
+                }
+
+                return Ok(());
+            }
+
+            seen.insert(positions.clone(), runs.len());
+            starts.push(positions);
+            let mut run = Count::new(dials.len());
+            self.run(block, dials, &mut run)?;
+            count.merge(&run)?;
+            runs.push(run);
         }
 
-        Answer::Numeric(zeros)
+        Ok(())
+    }
+
+    fn solve(&self, text: &str, part: usize) -> Result<Answer, SolveError> {
+        let input = Input::read(text)?;
+
+        arith::widen(|| { Ok(self.count_zeros::<usize>(&input)?.combined.answer(part)) },
+                     || { Ok(self.count_zeros::<u128>(&input)?.combined.answer(part)) },
+                     || { Ok(self.count_zeros::<BigUint>(&input)?.combined.answer(part)) })
     }
 }
 
+impl Day for Day1 {
+
+    // Compute Part 1 solution
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
+        self.solve(text, 1)
+    }
+
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
+        self.solve(text, 2)
+    }
+
+    fn solve_stream(&self, part: usize, reader: &mut dyn BufRead) -> Option<Result<Answer, SolveError>> {
+        if part != 1 && part != 2 {
+            return None;
+        }
+
+        Some(arith::widest(reader,
+                           |r| { Ok(self.stream_zeros::<usize>(r)?.combined.answer(part)) },
+                           |r| { Ok(self.stream_zeros::<u128>(r)?.combined.answer(part)) },
+                           |r| { Ok(self.stream_zeros::<BigUint>(r)?.combined.answer(part)) }))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // For a lock, each dial's zeros.  Otherwise, the start positions giving the fewest and most.
+    fn inspect(&self, text: &str) -> Option<Result<String, SolveError>> {
+        match Input::read(text) {
+            Ok(input) if !input.dials.is_empty() => return Some(self.lock_zeros(text).map(|lock| { lock.to_string() })),
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+
+        let by_start = match self.zeros_by_start(text) {
+            Ok(by_start) => by_start,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut text = format!("starting at {}: {} landings, {} passes\n", self.start,
+                               by_start[self.start].landings, by_start[self.start].passes);
+        let landings = extremes(by_start.iter().map(|z| { z.landings }));
+        let passes = extremes(by_start.iter().map(|z| { z.passes }));
+        for (name, ((fewest_at, fewest), (most_at, most))) in [("landings", landings), ("passes", passes)] {
+            text.push_str(&format!("{name}: fewest {fewest} starting at {fewest_at}, most {most} starting at {most_at}\n"));
+        }
+
+        Some(Ok(text))
+    }
+}
+
+impl std::fmt::Display for LockZeros {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (id, zeros) in &self.dials {
+            writeln!(f, "dial {id}: {} landings, {} passes", zeros.landings, zeros.passes)?;
+        }
+        writeln!(f, "all dials: {} landings, {} passes", self.combined.landings, self.combined.passes)?;
+        match self.all_zero {
+            Some(n) => writeln!(f, "all read zero after move {n}"),
+            None => writeln!(f, "never all read zero"),
+        }
+    }
+}
+
+// The zeros by start of a block run `times` times, from its zeros by start for one run and how
+// far that turns the dial.  Each run starts `turned` further on than the last, so the starts go
+// round in cycles of size / gcd(turned, size).  Every start gets the whole cycle's zeros once
+// for each time round, and then those of the runs left over.
+fn repeat_by_start(once: &[Zeros<u128>], turned: usize, times: usize) -> Result<Vec<Zeros<u128>>, SolveError> {
+    let size = once.len();
+    let cycles = gcd(turned, size);
+    let len = size / cycles;
+    let (rounds, left) = (times / len, times % len);
+
+    let mut repeated = vec![Zeros::new(); size];
+    for first in 0..cycles {
+        let mut cycle = Vec::with_capacity(len);
+        let mut start = first;
+        for _ in 0..len {
+            cycle.push(start);
+            start = ((start as u128 + turned as u128) % size as u128) as usize;
+        }
+
+        // Zeros so far going twice round the cycle, so that the runs left over never wrap.
+        let mut sums = vec![Zeros::new()];
+        for n in 0..2 * len {
+            let mut sum = sums[n].clone();
+            sum.merge(&once[cycle[n % len]])?;
+            sums.push(sum);
+        }
+
+        let whole = sums[len].times(rounds)?;
+        for (n, start) in cycle.iter().enumerate() {
+            let (to, from) = (&sums[n + left], &sums[n]);
+            repeated[*start] = Zeros { landings: to.landings - from.landings, passes: to.passes - from.passes };
+            repeated[*start].merge(&whole)?;
+        }
+    }
+
+    Ok(repeated)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// The first (index, value) with the smallest value, and the first with the largest.
+fn extremes(values: impl Iterator<Item = u128>) -> ((usize, u128), (usize, u128)) {
+    let mut fewest = (0, u128::MAX);
+    let mut most = (0, 0);
+    for (n, v) in values.enumerate() {
+        if v < fewest.1 {
+            fewest = (n, v);
+        }
+        if v > most.1 || n == 0 {
+            most = (n, v);
+        }
+    }
+
+    (fewest, most)
+}
+
 #[cfg(test)]
 mod test {
 
-    use crate::day1::{Day1, Input};
-    use crate::day::{Day, Answer};
+    use crate::day1::{Day1, Dial, Input, Turn, Zeros, extremes};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 L68
@@ -109,11 +844,12 @@ L82
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
+        let moves: Vec<isize> = input.moves().unwrap().collect();
                 
-        assert_eq!(input.moves.len(), 10);
-        assert_eq!(input.moves[0], -68);
-        assert_eq!(input.moves[2], 48);
+        assert_eq!(moves.len(), 10);
+        assert_eq!(moves[0], -68);
+        assert_eq!(moves[2], 48);
     }
 
     #[test]
@@ -132,5 +868,331 @@ L82
         let d: Day1 = Day1::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(6));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Every landing on zero is also counted in part 2.
+    fn test_generate() {
+        let d = Day1::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            assert_eq!(Input::read(&text).unwrap().moves().unwrap().count(), 20);
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p1 <= p2);
+        }
+    }
+
+    #[test]
+    // Positions wrap the Euclidean way, and big moves count every pass.
+    fn test_dial() {
+        let mut dial = Dial::new(100, 50).unwrap();
+        assert_eq!(dial.turn(-68), Turn { from: 50, to: 82, passes: 1, landed: false });
+        assert_eq!(dial.position(), 82);
+        assert_eq!(dial.turn(18), Turn { from: 82, to: 0, passes: 1, landed: true });
+        assert_eq!(dial.turn(-100), Turn { from: 0, to: 0, passes: 1, landed: true });
+        assert_eq!(dial.turn(-1_000_000_000_000), Turn { from: 0, to: 0, passes: 10_000_000_000, landed: true });
+        let turn = dial.turn(isize::MIN);
+        assert_eq!((turn.passes, turn.landed), (isize::MIN.unsigned_abs() / 100, false));
+        assert_eq!(dial.position(), 100 - isize::MIN.unsigned_abs() % 100);
+
+        let mut one = Dial::new(1, 0).unwrap();
+        assert_eq!(one.turn(isize::MAX), Turn { from: 0, to: 0, passes: isize::MAX as usize, landed: true });
+
+        assert!(Dial::new(10, 10).is_err());
+        assert!(Dial::new(0, 0).is_err());
+        assert!(Day1::with_dial(10, 10).try_part1(EXAMPLE1).is_err());
+    }
+
+    #[test]
+    // One event per move, each starting where the last stopped.
+    fn test_events() {
+        let events: Vec<Turn> = Day1::new().events(EXAMPLE1).unwrap().collect();
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[0], Turn { from: 50, to: 82, passes: 1, landed: false });
+        assert_eq!(events[2], Turn { from: 52, to: 0, passes: 1, landed: true });
+        assert!(events.windows(2).all(|w| { w[0].to == w[1].from }));
+        assert_eq!(events.iter().filter(|e| { e.landed }).count(), 3);
+        assert_eq!(events.iter().map(|e| { e.passes }).sum::<usize>(), 6);
+
+        // The move that passed zero most often.
+        let events = Day1::new().events("L50\nR1000\nL5\n").unwrap();
+        let (busiest, turn) = events.enumerate().max_by_key(|(_, e)| { e.passes }).unwrap();
+        assert_eq!((busiest, turn.passes), (1, 10));
+
+        assert!(Day1::new().events("L68\nX30\n").is_err());
+        assert!(Day1::new().events("dial a 4 1\nR1\n").is_err());
+
+        // Blocks are run as the moves are asked for.
+        let events: Vec<Turn> = Day1::new().events("repeat 1000000000000\nrepeat 0\nL1\nend\nR30\nend\n").unwrap().take(3).collect();
+        assert_eq!(events.iter().map(|e| { e.to }).collect::<Vec<_>>(), vec![80, 10, 40]);
+    }
+
+    // A program's moves, written out.
+    fn moves(text: &str) -> Vec<isize> {
+        Input::read(text).unwrap().moves().unwrap().collect()
+    }
+
+    // Count zeros one click at a time: (landings, passes including landings)
+    fn simulate_clicks(moves: &[isize]) -> (usize, usize) {
+        simulate_dial(moves, 100, 50)
+    }
+
+    // simulate_clicks() on a dial of any size, from any start.
+    fn simulate_dial(moves: &[isize], size: isize, start: isize) -> (usize, usize) {
+        let mut position = start;
+        let mut landings = 0;
+        let mut passes = 0;
+        for m in moves {
+            for _ in 0..m.abs() {
+                position = (position + m.signum()).rem_euclid(size);
+                if position == 0 {
+                    passes += 1;
+                }
+            }
+            if position == 0 {
+                landings += 1;
+            }
+        }
+
+        (landings, passes)
+    }
+
+    #[test]
+    // Compare both parts against a click-by-click simulation on random moves.
+    fn test_oracle() {
+        let d = Day1::new();
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(1, 30);
+            let text = d.generate(&mut rng, size).unwrap();
+            let (landings, passes) = simulate_clicks(&moves(&text));
+
+            assert_eq!(d.part1(&text), Answer::Numeric(landings), "input:\n{text}");
+            assert_eq!(d.part2(&text), Answer::Numeric(passes), "input:\n{text}");
+        }
+    }
+
+    #[test]
+    // The same on dials of other sizes and starts.
+    fn test_oracle_dials() {
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(1, 30);
+            let start = rng.below(size);
+            let text = Day1::new().generate(&mut rng, 20).unwrap();
+            let (landings, passes) = simulate_dial(&moves(&text), size as isize, start as isize);
+
+            let d = Day1::with_dial(size, start);
+            assert_eq!(d.part1(&text), Answer::Numeric(landings), "size {size}, start {start}, input:\n{text}");
+            assert_eq!(d.part2(&text), Answer::Numeric(passes), "size {size}, start {start}, input:\n{text}");
+        }
+    }
+
+    #[test]
+    // Zeros for every start at once match simulating from each start.
+    fn test_zeros_by_start() {
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(1, 30);
+            let text = Day1::new().generate(&mut rng, 20).unwrap();
+            let moves = moves(&text);
+
+            let by_start = Day1::with_dial(size, 0).zeros_by_start(&text).unwrap();
+            assert_eq!(by_start.len(), size);
+            for (start, zeros) in by_start.iter().enumerate() {
+                let (landings, passes) = simulate_dial(&moves, size as isize, start as isize);
+                assert_eq!((zeros.landings, zeros.passes), (landings as u128, passes as u128),
+                           "size {size}, start {start}, input:\n{text}");
+            }
+        }
+
+        // Blocks too, on dials whose size shares a factor with how far the block turns or not.
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(1, 30);
+            let text = random_program(&mut rng, 0);
+            let moves = moves(&text);
+
+            let by_start = Day1::with_dial(size, 0).zeros_by_start(&text).unwrap();
+            for (start, zeros) in by_start.iter().enumerate() {
+                let (landings, passes) = simulate_dial(&moves, size as isize, start as isize);
+                assert_eq!((zeros.landings, zeros.passes), (landings as u128, passes as u128),
+                           "size {size}, start {start}, input:\n{text}");
+            }
+        }
+
+        let by_start = Day1::new().zeros_by_start(EXAMPLE1).unwrap();
+        assert_eq!((by_start[50].landings, by_start[50].passes), (3, 6));
+        assert!(Day1::new().zeros_by_start("set 5\nR1\n").is_err());
+        assert!(Day1::new().zeros_by_start("dial a 4 1\nR1\n").is_err());
+        assert!(Day1::with_dial(0, 0).zeros_by_start(EXAMPLE1).is_err());
+        assert!(Day1::with_dial(10, 10).zeros_by_start(EXAMPLE1).is_err());
+    }
+
+    #[test]
+    // Ties go to the first.
+    fn test_extremes() {
+        assert_eq!(extremes([3, 1, 4, 1, 5, 9, 2, 9].into_iter()), ((1, 1), (5, 9)));
+        assert_eq!(extremes([0, 0].into_iter()), ((0, 0), (0, 0)));
+    }
+
+    #[test]
+    // Inspecting reports the best and worst starts.
+    fn test_inspect() {
+        let text = Day1::with_dial(4, 1).inspect("R1\nR2\nL3\n").unwrap().unwrap();
+        assert_eq!(text, "starting at 1: 1 landings, 1 passes\n\
+                          landings: fewest 0 starting at 2, most 1 starting at 0\n\
+                          passes: fewest 1 starting at 0, most 2 starting at 2\n");
+
+        // Blocks are counted from every start without running them.
+        let text = Day1::new().inspect("repeat 1000000000000\nR1\nend\n").unwrap().unwrap();
+        assert_eq!(text, "starting at 50: 10000000000 landings, 10000000000 passes\n\
+                          landings: fewest 10000000000 starting at 0, most 10000000000 starting at 0\n\
+                          passes: fewest 10000000000 starting at 0, most 10000000000 starting at 0\n");
+
+        // The start has to be on the dial.
+        assert_eq!(Day1::with_dial(10, 10).inspect("R5\n"),
+                   Some(Err(SolveError::Unsolvable(String::from("Can't start at 10 on a dial of size 10.")))));
+    }
+
+    #[test]
+    // Each dial counts its own moves and the untagged ones, as if it were alone.
+    fn test_lock() {
+        let text = "dial a 4 1\ndial b 6 2\nL1\nb:L1\nR9\na:L6\nb:R13\n";
+        let lock = Day1::new().lock_zeros(text).unwrap();
+        let (a, b) = (simulate_dial(&[-1, 9, -6], 4, 1), simulate_dial(&[-1, -1, 9, 13], 6, 2));
+        let zeros = |(landings, passes): (usize, usize)| { Zeros { landings: landings as u128, passes: passes as u128 } };
+        assert_eq!(lock.dials, vec![(String::from("a"), zeros(a)), (String::from("b"), zeros(b))]);
+        assert_eq!(lock.combined, zeros((a.0 + b.0, a.1 + b.1)));
+        assert_eq!(lock.all_zero, Some(2));
+        assert!(Day1::new().inspect(text).unwrap().unwrap().ends_with("all read zero after move 2\n"));
+
+        // Without declarations, it's the usual dial.
+        let lock = Day1::new().lock_zeros(EXAMPLE1).unwrap();
+        assert_eq!(lock.dials, vec![(String::from("dial"), zeros((3, 6)))]);
+        assert_eq!(lock.all_zero, Some(3));
+        assert_eq!(Day1::new().lock_zeros("dial a 4 1\ndial b 6 2\nR3\n").unwrap().all_zero, None);
+
+        let error = |text: &str| { Day1::new().lock_zeros(text).unwrap_err() };
+        assert_eq!(error("dial a 4 1\nc:L1\n"), SolveError::parse(2, "No dial 'c'"));
+        assert_eq!(error("dial a 4 1\ndial a 6 2\n"), SolveError::parse(2, "Dial 'a' is declared twice"));
+        assert_eq!(error("L1\ndial a 4 1\n"), SolveError::parse(2, "Dials must be declared before the moves"));
+        assert_eq!(error("dial a 4\n"), SolveError::parse(1, "Bad dial 'dial a 4'"));
+        assert_eq!(error("dial a 4 4\n"), SolveError::parse(1, "Can't start at 4 on a dial of size 4."));
+        assert_eq!(error("dial a 4 1\na:X1\n"), SolveError::parse(2, "Bad move 'X1'"));
+
+        // The parts count the zeros of all the dials, streamed or not.
+        let d = Day1::new();
+        assert_eq!(d.part1(text), Answer::Numeric(a.0 + b.0));
+        assert_eq!(d.part2(text), Answer::Numeric(a.1 + b.1));
+        assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(Ok(Answer::Numeric(a.1 + b.1))));
+        assert_eq!(d.part1("dial a 10 0\na:R10\n"), Answer::Numeric(1));
+        assert!(d.events(text).is_err());
+        assert!(d.zeros_by_start(text).is_err());
+
+        // Locks are programs like any other, with comments, sets and blocks.
+        let text = "dial a 10 0  # first\n\ndial b 4 1\nrepeat 2\n  a:R1\nend\nb:set 0\nR8\n";
+        let lock = d.lock_zeros(text).unwrap();
+        assert_eq!(lock.dials, vec![(String::from("a"), zeros((1, 1))), (String::from("b"), zeros((1, 2)))]);
+        assert_eq!(lock.all_zero, Some(3));
+        assert_eq!(d.part1(text), Answer::Numeric(2));
+        assert_eq!(d.part2(text), Answer::Numeric(3));
+        assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(Ok(Answer::Numeric(3))));
+        assert_eq!(error("repeat 2\ndial a 4 1\nend\n"), SolveError::parse(2, "Dials must be declared before the moves"));
+
+        let text = "dial a 3 0\ndial b 5 0\nrepeat 1000000000000\nR1\nend\n";
+        let lock = d.lock_zeros(text).unwrap();
+        assert_eq!(lock.combined, zeros((533_333_333_333, 533_333_333_333)));
+        assert_eq!(lock.all_zero, Some(15));
+    }
+
+    // A random program of moves, with nested repeat blocks and comments.
+    fn random_program(rng: &mut Rng, depth: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..rng.range(1, 5) {
+            if depth < 3 && rng.chance(1, 3) {
+                text.push_str(&format!("repeat {}  # block\n", rng.range(0, 6)));
+                text.push_str(&random_program(rng, depth + 1));
+                text.push_str("end\n\n");
+            }
+            else {
+                let dir = if rng.chance(1, 2) { 'L' } else { 'R' };
+                text.push_str(&format!("{dir}{}\n", rng.range(0, 250)));
+            }
+        }
+
+        text
+    }
+
+    #[test]
+    // Programs with blocks count the same zeros as their moves written out, streamed or not.
+    fn test_program() {
+        let d = Day1::new();
+        for seed in 0..200 {
+            let text = random_program(&mut Rng::new(seed), 0);
+            let (landings, passes) = simulate_clicks(&moves(&text));
+
+            assert_eq!(d.part1(&text), Answer::Numeric(landings), "input:\n{text}");
+            assert_eq!(d.part2(&text), Answer::Numeric(passes), "input:\n{text}");
+            assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(Ok(Answer::Numeric(passes))));
+        }
+
+        // A set clicks past nothing.
+        let text = "# from 50\nset 99\nR1\nset 0\nL1  # 99\nset 5\nL5\n";
+        assert_eq!(d.part1(text), Answer::Numeric(2));
+        assert_eq!(d.part2(text), Answer::Numeric(2));
+        assert!(d.events(text).is_err());
+    }
+
+    #[test]
+    // Blocks run a trillion times without making a trillion moves.
+    fn test_repeat() {
+        let d = Day1::new();
+        for text in ["repeat 1000000000000\nR1\nend\n", "repeat 1000000\nrepeat 1000000\nR1\nend\nend\n"] {
+            assert_eq!(d.part1(text), Answer::Numeric(10_000_000_000));
+            assert_eq!(d.part2(text), Answer::Numeric(10_000_000_000));
+        }
+
+        // Where the dial ends up matters to what comes after.
+        let text = "repeat 1000000000003\nR7\nset 3\nR1\nend\nL4\n";
+        assert_eq!(d.part1(text), Answer::Numeric(1));
+        assert_eq!(d.part2(text), Answer::Numeric(1));
+    }
+
+    #[test]
+    // Programs that don't parse or can't run.
+    fn test_program_errors() {
+        let d = Day1::new();
+        assert_eq!(d.try_part1("L1\nrepeat x\nend\n"), Err(SolveError::parse(2, "Bad repeat 'repeat x'")));
+        assert_eq!(d.try_part1("L1\nend\n"), Err(SolveError::parse(2, "'end' without a 'repeat'")));
+        assert_eq!(d.try_part1("repeat 2\nrepeat 3\nL1\nend\n"), Err(SolveError::parse(1, "'repeat' without an 'end'")));
+        assert_eq!(d.solve_stream(1, &mut "repeat 2\nL1\n".as_bytes()),
+                   Some(Err(SolveError::parse(1, "'repeat' without an 'end'"))));
+        assert_eq!(d.try_part1("set -1\n"), Err(SolveError::parse(1, "Bad set 'set -1'")));
+        assert_eq!(d.try_part1("repeat 2\nset 100\nend\n"),
+                   Err(SolveError::Unsolvable(String::from("Can't set 100 on a dial of size 100."))));
+    }
+
+    #[test]
+    // Streaming a line at a time gives the same answers, and the same errors.
+    fn test_stream() {
+        let d = Day1::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 50).unwrap();
+            assert_eq!(d.solve_stream(1, &mut text.as_bytes()), Some(d.try_part1(&text)));
+            assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(d.try_part2(&text)));
+        }
+
+        let bad = "L68\nL30\nX48\n";
+        assert_eq!(d.solve_stream(1, &mut bad.as_bytes()), Some(Err(SolveError::parse(3, "Bad move 'X48'"))));
+        assert_eq!(d.solve_stream(3, &mut EXAMPLE1.as_bytes()), None);
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day1::new(), &[EXAMPLE1]);
+    }
 }
@@ -1,11 +1,12 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use std::cmp::min;
-use std::mem::swap;
 use num_rational::Rational32;
 use regex::Regex;
 use num_traits::Signed;
 
-use crate::day::{Day, Answer};
+use crate::day::{Day, Answer, SolveError, DEFAULT_STRATEGY};
+use crate::rng::Rng;
+use crate::trace;
 
 // Represents one of the machines described in the Advent of Code Day 10, 2025 puzzle.
 struct MachineDesc {
@@ -23,8 +24,19 @@ impl MachineDesc {
     const BUTTON_RE: &str = "\\(([0-9](,[0-9])*)\\)";  // Matches (1,2,3), cap[1]: 1,2,3
     const JOLTAGE_RE: &str = "\\{([0-9]+(,[0-9]+)*)\\}"; // Matches {10,8,2}, cap[1]: 10,8,2
 
+    // Limits on a machine.  Buttons are wired with single digits, so lights past the tenth
+    // could never change.  Up to 16 buttons keeps the GF(2) search over free buttons small, and
+    // joltages up to 1000 keep the elimination exact in Rational32.
+    const MAX_LIGHTS: usize = 10;
+    const MAX_BUTTONS: usize = 16;
+    const MAX_JOLTAGE: usize = 1000;
+
+    // Most sets of free button presses the joltage search tries before giving up.
+    const MAX_SEARCH: usize = 1_000_000;
+
     // Take a line from input file, construct the corresponding machine.
-    fn from_line(line: &str) -> MachineDesc {
+    fn from_line(line_no: usize, line: &str) -> Result<MachineDesc, SolveError> {
+        let error = |message: String| { SolveError::parse(line_no + 1, message) };
 
         let mut start_indicator = 0;
         let mut button_masks = Vec::new();
@@ -36,8 +48,13 @@ impl MachineDesc {
         let joltage_re = Regex::new(Self::JOLTAGE_RE).unwrap();
 
         // Process start indicator
+        let n_lights;
         if let Some(cap) = activation_re.captures(line) {
             // Got start indicator, e.g. "..##.", in cap[1]
+            n_lights = cap[1].len();
+            if n_lights > Self::MAX_LIGHTS {
+                return Err(error(format!("{n_lights} lights, at most {} allowed.", Self::MAX_LIGHTS)));
+            }
             let mut mask = 1;
             for c in cap[1].chars() {
                 match c {
@@ -53,7 +70,7 @@ impl MachineDesc {
             }
         }
         else {
-            panic!("No activation pattern found.")
+            return Err(error(String::from("No activation pattern found.")));
         }
 
         // Process buttons
@@ -65,6 +82,9 @@ impl MachineDesc {
             // note that only single digit values appear so we use to_digit, not parse.
             for c in cap[1].chars() {
                 if let Some(n) = c.to_digit(10) {
+                    if n as usize >= n_lights {
+                        return Err(error(format!("Button wired to light {n}, but there are {n_lights} lights.")));
+                    }
                     button_bitmask |= 1 << n;
                     button_vec.push(n as usize);
                 }
@@ -72,31 +92,37 @@ impl MachineDesc {
             button_masks.push(button_bitmask);
             button_vecs.push(button_vec);
         }
-        assert!(button_masks.len() > 0, "No buttons found.");
+        if button_masks.is_empty() {
+            return Err(error(String::from("No buttons found.")));
+        }
+        if button_masks.len() > Self::MAX_BUTTONS {
+            return Err(error(format!("{} buttons, at most {} allowed.", button_masks.len(), Self::MAX_BUTTONS)));
+        }
 
         // Process joltage
         if let Some(cap) = joltage_re.captures(line) {
             // Got joltage, e.g. "1,12,9", in cap[1]
             // Note multi-digit values appear, so we use parse(), not to_digit.
             for s in cap[1].split(",") {
-                if let Ok(n) = s.parse::<usize>() {
-                    joltage.push(n);
-                }
-                else {
-                    panic!("Bad parse for joltage: '{s}'")
+                match s.parse::<usize>() {
+                    Ok(n) if n <= Self::MAX_JOLTAGE => joltage.push(n),
+                    _ => return Err(error(format!("Bad parse for joltage: '{s}'"))),
                 }
             }
         }
         else {
-            panic!("No joltage found.");
+            return Err(error(String::from("No joltage found.")));
+        }
+        if joltage.len() != n_lights {
+            return Err(error(format!("{} joltages for {n_lights} lights.", joltage.len())));
         }
 
-        MachineDesc { start_indicator, button_masks, button_vecs, joltage }
+        Ok(MachineDesc { start_indicator, button_masks, button_vecs, joltage })
     }
 
     // Find the shortest activation sequence
-    fn act_seq(&self) -> Vec<usize> {
-        // println!("Looking for activation state: 0x{:02x}", self.start_indicator);
+    fn act_seq(&self) -> Result<Vec<usize>, SolveError> {
+        trace::note(|| { format!("Looking for activation state: 0x{:02x}", self.start_indicator) });
 
         // states to explore from
         let mut to_explore: VecDeque<usize> = VecDeque::new();
@@ -145,12 +171,87 @@ impl MachineDesc {
         }
 
         if let Some(seq) = found {
-            // println!("Found activation seq: {seq:?}");
-            seq
+            trace::note(|| { format!("Found activation seq: {seq:?}") });
+            Ok(seq)
         }
         else {
-            panic!("No activation sequence found.")
+            Err(SolveError::Unsolvable(String::from("No activation sequence found.")))
+        }
+    }
+
+    // Find the shortest activation sequence by solving a linear system over GF(2).
+    //
+    // Pressing a button twice cancels out, so an optimal sequence presses each button at most
+    // once.  The buttons pressed form a solution x of A x = b (mod 2), where column j of A is
+    // button j's mask and b is the start indicator.  Reduce the system, then try every assignment
+    // of the free variables and keep the solution that presses the fewest buttons.
+    fn act_seq_linalg(&self) -> Result<Vec<usize>, SolveError> {
+        let n_buttons = self.button_masks.len();
+
+        // One row per indicator light: bitmask of buttons that toggle it, and the desired state.
+        let n_lights = self.button_masks.iter()
+            .chain(std::iter::once(&self.start_indicator))
+            .map(|mask| { usize::BITS - mask.leading_zeros() })
+            .max()
+            .unwrap_or(0) as usize;
+        let mut rows: Vec<(usize, bool)> = (0..n_lights)
+            .map(|light| {
+                let mut row = 0;
+                for (id, button) in self.button_masks.iter().enumerate() {
+                    if button & (1 << light) != 0 {
+                        row |= 1 << id;
+                    }
+                }
+                (row, self.start_indicator & (1 << light) != 0)
+            })
+            .collect();
+
+        // Gauss-Jordan elimination to reduced row echelon form.
+        let mut pivots = Vec::new();
+        for col in 0..n_buttons {
+            let r = pivots.len();
+            if let Some(found) = (r..rows.len()).find(|&row| { rows[row].0 & (1 << col) != 0 }) {
+                rows.swap(r, found);
+                for row in 0..rows.len() {
+                    if row != r && rows[row].0 & (1 << col) != 0 {
+                        rows[row].0 ^= rows[r].0;
+                        rows[row].1 ^= rows[r].1;
+                    }
+                }
+                pivots.push(col);
+            }
         }
+
+        // Any remaining row with no buttons but a lit indicator can't be satisfied.
+        if rows[pivots.len()..].iter().any(|(_, lit)| { *lit }) {
+            return Err(SolveError::Unsolvable(String::from("No activation sequence found.")));
+        }
+
+        let free: Vec<usize> = (0..n_buttons).filter(|col| { !pivots.contains(col) }).collect();
+
+        // Try every assignment of the free buttons, deriving the pivot buttons from it.
+        let mut best: Option<usize> = None;
+        for assignment in 0..(1_usize << free.len()) {
+            let mut pressed = 0;
+            for (bit, col) in free.iter().enumerate() {
+                if assignment & (1 << bit) != 0 {
+                    pressed |= 1 << col;
+                }
+            }
+            for (r, col) in pivots.iter().enumerate() {
+                let (row, lit) = rows[r];
+                if lit ^ ((row & pressed).count_ones() % 2 == 1) {
+                    pressed |= 1 << col;
+                }
+            }
+
+            if best.is_none_or(|b| { pressed.count_ones() < b.count_ones() }) {
+                best = Some(pressed);
+            }
+        }
+
+        let pressed = best.unwrap_or(0);
+        Ok((0..n_buttons).filter(|id| { pressed & (1 << id) != 0 }).collect())
     }
 
     // Print a matrix (for debugging.)
@@ -175,8 +276,9 @@ impl MachineDesc {
 
     // Gaussian Elimination step used to solve the joltage sequence.
     // Since we ultimately want non-negative integer solutions, this routine uses Rational32
-    // to represent the components.
-    fn gauss_elim(m: &mut Vec<Vec<Rational32>>) {
+    // to represent the components.  col_seq follows the column swaps, so col_seq[c] is the button
+    // column c ends up holding.
+    fn gauss_elim(m: &mut Vec<Vec<Rational32>>, col_seq: &mut [usize]) {
 
         let n_rows = m.len();
         let n_cols = m[0].len();
@@ -203,8 +305,8 @@ impl MachineDesc {
             m.swap(n, max_row);  // swap rows
             for row in 0..n_rows {
                 m[row].swap(n, max_col);  // swap cols
-                // col_seq.swap(n, max_col);    // keep track of col swaps
             }
+            col_seq.swap(n, max_col);    // keep track of col swaps
 
             // println!("Pivoted for ({n}, {n}):");
             // MachineDesc::print_m(m);
@@ -251,13 +353,17 @@ impl MachineDesc {
     // non-integer components.  The loop will continue until it finds the first valid one, then
     // it computes how much further (worst case) it must search to exhaust all valid combinations.
     // It loops through all those then returns the best one found.
-
-    fn back_sub(m: & Vec<Vec<Rational32>>) -> Vec<Rational32> {
+    //
+    // No button can be pressed more times than the lowest joltage it raises, and `max_presses`
+    // gives that for each column.  Only free values within those are tried, and if no solution
+    // turns up among them, there isn't one.  The search gives up after trying MAX_SEARCH
+    // combinations, which many free buttons and high joltages can need.
+    fn back_sub(m: & Vec<Vec<Rational32>>, max_presses: &[usize]) -> Result<Vec<usize>, SolveError> {
         let n_rows = m.len();
         let n_cols = m[0].len();
 
-        let mut soln = vec![Rational32::ZERO; n_cols-1];
-        let mut min_soln = vec![Rational32::ZERO; n_cols-1];
+        let mut soln = vec![0; n_cols-1];
+        let mut min_soln = vec![0; n_cols-1];
         let mut min_soln_sum: Option<usize> = None;
 
         // count dependent variables
@@ -271,68 +377,95 @@ impl MachineDesc {
         // number of independent variables
         let i_vars = n_cols-1 - d_vars;
 
-        // println!("There are {d_vars} dependent variables.  {i_vars} are free.");
+        // Rows left over after elimination must read 0 = 0.
+        if m[d_vars..].iter().any(|row| { row[n_cols-1] != Rational32::ZERO }) {
+            return Err(SolveError::Unsolvable(String::from("Joltages can't be reached.")));
+        }
 
-        // All the sets of unconstrained values to test on one iteration through loop.
-        let mut test_vectors = HashSet::<Vec<Rational32>>::new();
-        test_vectors.insert(vec![Rational32::ZERO; i_vars]);
+        trace::note(|| { format!("There are {d_vars} dependent variables.  {i_vars} are free.") });
+
+        // Back substitute once, up front, writing each dependent variable as a constant plus a
+        // multiple of each free variable: dep[r] = [constant, coefficient of free 0, ...].
+        let mut dep = vec![vec![Rational32::ZERO; i_vars+1]; d_vars];
+        for r in (0..d_vars).rev() {
+            let mut row = vec![Rational32::ZERO; i_vars+1];
+            row[0] = m[r][n_cols-1];
+            for c in r+1..n_cols-1 {
+                if c < d_vars {
+                    for k in 0..=i_vars {
+                        row[k] -= m[r][c] * dep[c][k];
+                    }
+                }
+                else {
+                    row[c-d_vars+1] -= m[r][c];
+                }
+            }
+            dep[r] = row;
+        }
 
-        // All the sets of unconstrained values to test on the next iteration through the loop.
-        let mut next_test_vectors = HashSet::<Vec<Rational32>>::new();
+        // Scale each row to integers, so a set of free values is tried with integer arithmetic.
+        // A dependent variable is valid if its scaled value is non-negative and divides evenly.
+        let too_big = || { SolveError::Unsolvable(String::from("Joltage search coefficients are too large.")) };
+        let mut scaled = Vec::new();
+        for row in dep.iter() {
+            let mut scale: i128 = 1;
+            for c in row.iter() {
+                let d = *c.denom() as i128;
+                scale = (scale / MachineDesc::gcd(scale, d)).checked_mul(d).ok_or_else(too_big)?;
+            }
+            let coeffs: Vec<i128> = row.iter()
+                .map(|c| { (scale / *c.denom() as i128).checked_mul(*c.numer() as i128) })
+                .collect::<Option<Vec<i128>>>()
+                .ok_or_else(too_big)?;
+            scaled.push((scale, coeffs));
+        }
 
         // Max number of loop iterations necessary.  (This is unknown until the first solution pops out.)
         let mut max_loops = None;
 
-        // how many times the loop has iterated.
+        // how many times the loop has iterated, and how many sets of values have been tried.
         let mut loops = 0;
+        let mut tried = 0;
 
         // Iterate over all possible values of unconstrained variables.
+        let mut v = vec![0; i_vars];
         loop {
-            // Iterate over contents of test_vectors (all combos of unconstrained values with a particular sum, 0, 1, 2...)
-            for v in &test_vectors {
+            // Try every combo of unconstrained values with a particular sum (0, 1, 2...)
+            let finished = MachineDesc::each_with_sum(&mut v, &max_presses[d_vars..], 0, loops, &mut |v| {
+                tried += 1;
+                if tried > Self::MAX_SEARCH {
+                    return false;
+                }
 
                 // Generate the solution vector
-                // This works by stuffing the unconstrained values for this round then using back substitution
-                // to find all the other components of the solution.
-                for n in 0..i_vars {
-                    soln[d_vars+n] = v[n];
-                }
-                for n in 0..d_vars {
-                    let r = d_vars - n - 1;  // row to solve with back substitution
-                    let mut sum = Rational32::ZERO;
-                    for c in r+1..n_cols-1 {
-                        sum += m[r][c] * soln[c];
+                // This works by stuffing the unconstrained values for this round then using the
+                // scaled rows to find all the other components of the solution.
+                soln[d_vars..].copy_from_slice(v);
+                for (r, (scale, coeffs)) in scaled.iter().enumerate() {
+                    let mut value = coeffs[0];
+                    for (k, x) in v.iter().enumerate() {
+                        value += coeffs[k+1] * *x as i128;
                     }
-                    soln[r] = m[r][n_cols-1] - sum;
-                }
 
-                // Test whether solution is valid (non-negative integers.)
-                // If solution is valid, return it.
-                let all_good = soln.iter()
-                    .map(|c| { 
-                        (*c >= Rational32::ZERO) && c.is_integer()
-                    })
-                    .fold(true, |acc, good| { acc && good });
-
-                // Test whether good solutions are better than previous ones.
-                if all_good {
-                    // This is a good candidate.  Check it's sum and see if it's less that what we had
-                    let sum = soln.iter().map(|c| {*c.numer() as usize}).sum();
-                    match min_soln_sum {
-                        Some(old_min) => {
-                            // We have a previous min, check to see if this one is better.
-                            if sum < old_min {
-                                min_soln_sum = Some(sum);
-                                min_soln = soln.clone();
-                            }
-                        }
-                        None => {
-                            // This is the first solution seen, register it as the min so far.
-                            min_soln_sum = Some(sum);
-                            min_soln = soln.clone();
-                        }
+                    // Test whether the component is valid (a non-negative integer.)
+                    if value < 0 || value % scale != 0 {
+                        return true;
                     }
+                    soln[r] = (value / scale) as usize;
+                }
+
+                // This is a good candidate.  Check it's sum and see if it's less that what we had
+                let sum = soln.iter().sum();
+                if min_soln_sum.is_none_or(|old_min| { sum < old_min }) {
+                    min_soln_sum = Some(sum);
+                    min_soln = soln.clone();
                 }
+
+                true
+            });
+            if !finished {
+                return Err(SolveError::Unsolvable(format!("Gave up after trying {} sets of {i_vars} free buttons.",
+                                                          Self::MAX_SEARCH)));
             }
 
             // Break out of outer loop if there are no free variables -- we only have one possible solution
@@ -347,36 +480,55 @@ impl MachineDesc {
                     break;
                 }
             }
+            else if loops > max_presses[d_vars..].iter().sum() {
+                break;
+            }
 
             // If we haven't determined max_loops yet but we found a solution on this iteration,
             // We can determin max_loops now.
             if max_loops.is_none() && min_soln_sum.is_some() {
                 // The max number of loops should be loops + the largest component of the initial solution.
-                let max = min_soln.iter().map(|c| { c.numer().abs() as usize }).max().unwrap();
+                let max = *min_soln.iter().max().unwrap();
                 max_loops = Some(loops+max);
             }
+        }
 
-            // Generate next set of test vectors.
-            // We take the last set of test vectors, add one to each potential position, then put
-            // the result into the HashSet.  Duplicates are generated and discarded in the process.
-            next_test_vectors.clear();
-            // Use each member of test_vectors to generate some next test vectors
-            for v in &test_vectors {
-                for position in 0..i_vars {
-                    let mut new_v = v.clone();
-                    new_v[position] += 1;
-                    next_test_vectors.insert(new_v);
-                }
+        if min_soln_sum.is_none() {
+            return Err(SolveError::Unsolvable(String::from("Joltages can't be reached.")));
+        }
+
+        Ok(min_soln)
+    }
+
+    // Call f with v[pos..] set to every combination of values up to `max` summing to
+    // `remaining`, until f returns false.  Returns whether every combination was tried.
+    fn each_with_sum(v: &mut [usize], max: &[usize], pos: usize, remaining: usize, f: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        if remaining > max[pos..].iter().sum() {
+            return true;
+        }
+        if pos + 1 >= v.len() {
+            if let Some(last) = v.get_mut(pos) {
+                *last = remaining;
             }
+            return f(v);
+        }
 
-            // Swap next_test_vectors into test_vectors before repeating loop.
-            swap(&mut test_vectors, &mut next_test_vectors);
+        for n in 0..=min(remaining, max[pos]) {
+            v[pos] = n;
+            if !MachineDesc::each_with_sum(v, max, pos + 1, remaining - n, f) {
+                return false;
+            }
         }
 
-        min_soln
+        true
     }
 
-    fn joltage_seq(&self) -> Vec<usize> {
+    // Greatest common divisor, for scaling rational rows to integers.
+    fn gcd(a: i128, b: i128) -> i128 {
+        if b == 0 { a.abs() } else { MachineDesc::gcd(b, a % b) }
+    }
+
+    fn joltage_seq(&self) -> Result<Vec<usize>, SolveError> {
         // Create augmented matrix for gaussian elimination
         let mut m = vec![vec![Rational32::ZERO; self.button_vecs.len()+1]; self.joltage.len()];
 
@@ -394,16 +546,17 @@ impl MachineDesc {
         // MachineDesc::print_m(&m);
 
         // Gaussian Elimination followed by back substitution
-        MachineDesc::gauss_elim(&mut m);
-        let soln = MachineDesc::back_sub(&m);
+        let mut col_seq: Vec<usize> = (0..self.button_vecs.len()).collect();
+        MachineDesc::gauss_elim(&mut m, &mut col_seq);
 
-        // Convert the solution from Vec<Rational32> to Vec<usize>
-        let seq = soln.iter().map(|c| {
-                *c.numer() as usize
-            }).collect();
+        // Each column's button can be pressed at most as many times as its lowest joltage.
+        let max_presses: Vec<usize> = col_seq.iter()
+            .map(|b| { self.button_vecs[*b].iter().map(|l| { self.joltage[*l] }).min().unwrap_or(0) })
+            .collect();
+        let seq = MachineDesc::back_sub(&m, &max_presses)?;
 
         // Return the optimal sequence that was found
-        seq
+        Ok(seq)
     }
 
 }
@@ -414,16 +567,89 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut machines = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
-            machines.push(MachineDesc::from_line(line));
+            machines.push(MachineDesc::from_line(line_no, line)?);
         }
 
-        Input { machines }
+        Ok(Input { machines })
+    }
+
+    // Every machine from_line() rejects, and every button it would misread.  Buttons are wired
+    // to single digit lights, so a button like "(10,2)" doesn't match and is skipped.
+    fn lint(text: &str) -> Vec<SolveError> {
+        let group_re = Regex::new("\\([^)]*\\)").unwrap();
+        let button_re = Regex::new(&format!("^{}$", MachineDesc::BUTTON_RE)).unwrap();
+        let mut problems = Vec::new();
+
+        for (line_no, line) in text.lines().enumerate() {
+            for group in group_re.find_iter(line) {
+                if !button_re.is_match(group.as_str()) {
+                    problems.push(SolveError::parse(line_no + 1, format!("Button '{}' isn't single digits.", group.as_str())));
+                }
+            }
+            if let Err(e) = MachineDesc::from_line(line_no, line) {
+                problems.push(e);
+            }
+        }
+
+        problems
+    }
+
+    // Random input in the format read() accepts: `size` machines.
+    //
+    // Each has 3 to 10 lights (button wiring is single digits) and up to two more buttons than
+    // lights.  The indicator pattern and joltages come from actual button presses, so every
+    // machine can be started.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..size {
+            let n_lights = rng.range(3, 10);
+            let n_buttons = rng.range(2, n_lights + 2);
+
+            let buttons: Vec<Vec<usize>> = (0..n_buttons)
+                .map(|_| {
+                    let wired: Vec<usize> = (0..n_lights).filter(|_| { rng.chance(1, 3) }).collect();
+                    if wired.is_empty() { vec![rng.below(n_lights)] } else { wired }
+                })
+                .collect();
+
+            // Indicator: toggle a random, non-empty, set of buttons, retrying if it comes out dark.
+            let mut indicator = vec![false; n_lights];
+            while !indicator.iter().any(|lit| { *lit }) {
+                for button in buttons.iter().filter(|_| { rng.chance(1, 2) }) {
+                    for light in button {
+                        indicator[*light] = !indicator[*light];
+                    }
+                }
+            }
+
+            // Joltage: press each button a random number of times.
+            let mut joltage = vec![0; n_lights];
+            for button in buttons.iter() {
+                let presses = rng.range(0, 10);
+                for light in button {
+                    joltage[*light] += presses;
+                }
+            }
+
+            let pattern: String = indicator.iter().map(|lit| { if *lit { '#' } else { '.' } }).collect();
+            let buttons: Vec<String> = buttons.iter()
+                .map(|b| {
+                    let lights: Vec<String> = b.iter().map(|l| { l.to_string() }).collect();
+                    format!("({})", lights.join(","))
+                })
+                .collect();
+            let joltage: Vec<String> = joltage.iter().map(|j| { j.to_string() }).collect();
+
+            text.push_str(&format!("[{pattern}] {} {{{}}}\n", buttons.join(" "), joltage.join(",")));
+        }
+
+        text
     }
 }
 
@@ -434,45 +660,74 @@ impl Day10 {
     pub const fn new() -> Self {
         Self { }
     }
+
+    // Sum of the shortest activation sequence lengths, using the given search for each machine.
+    fn activation_presses(text: &str, act_seq: fn(&MachineDesc) -> Result<Vec<usize>, SolveError>) -> Result<Answer, SolveError> {
+        // Read input file into Input struct
+        let input = Input::read(text)?;
+
+        let mut sum = 0;
+        for m in input.machines.iter() {
+            sum += act_seq(m)?.len();
+        }
+
+        Ok(Answer::Numeric(sum))
+    }
 }
 
 impl Day for Day10 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        // Read input file into Input struct
-        let input = Input::read(text);
-
-        let sum = input.machines.iter()
-            .map(|m| {
-                m.act_seq().len()
-            })
-            .sum();
-
-        Answer::Numeric(sum)
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
+        Day10::activation_presses(text, MachineDesc::act_seq)
     }
 
     // Compute part 2 solution
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
         // compute the optimal joltage sequence for each machine and sum all the components
-        let sum = input.machines.iter()
-            .map(|m| {
-                m.joltage_seq().iter().sum::<usize>()
-            })
-            .sum();
+        let mut sum = 0;
+        for m in input.machines.iter() {
+            sum += m.joltage_seq()?.iter().sum::<usize>();
+        }
+
+        Ok(Answer::Numeric(sum))
+    }
+
+    fn strategies(&self, part: usize) -> Vec<&'static str> {
+        match part {
+            1 => vec!["bfs", "linalg"],
+            _ => vec![DEFAULT_STRATEGY],
+        }
+    }
+
+    fn solve_with(&self, part: usize, strategy: &str, text: &str) -> Result<Answer, SolveError> {
+        match (part, strategy) {
+            (1, DEFAULT_STRATEGY | "bfs") => self.try_part1(text),
+            (1, "linalg") => Day10::activation_presses(text, MachineDesc::act_seq_linalg),
+            (2, DEFAULT_STRATEGY) => self.try_part2(text),
+            _ => Err(SolveError::NoStrategy { part, strategy: strategy.to_string() }),
+        }
+    }
+
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        Some(Input::lint(text))
+    }
 
-        Answer::Numeric(sum)
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day10::{Day10, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError, DEFAULT_STRATEGY};
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     // Examples from the challenge
     const EXAMPLE1: &str = "\
@@ -494,7 +749,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         assert_eq!(input.machines.len(), 3);
 
         assert_eq!(input.machines[0].start_indicator, 0b0110);
@@ -519,48 +774,58 @@ mod test {
     #[test]
     // Test with part 1 examples
     fn test_activate() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        let activation_seq = input.machines[0].act_seq();
+        let activation_seq = input.machines[0].act_seq().unwrap();
         assert_eq!(activation_seq.len(), 2);
 
-        let activation_seq = input.machines[1].act_seq();
+        let activation_seq = input.machines[1].act_seq().unwrap();
         assert_eq!(activation_seq.len(), 3);
 
-        let activation_seq = input.machines[2].act_seq();
+        let activation_seq = input.machines[2].act_seq().unwrap();
         assert_eq!(activation_seq.len(), 2);   
     }
 
+    #[test]
+    // Part 1 examples again, solved as a linear system over GF(2).
+    fn test_activate_linalg() {
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        assert_eq!(input.machines[0].act_seq_linalg().unwrap().len(), 2);
+        assert_eq!(input.machines[1].act_seq_linalg().unwrap().len(), 3);
+        assert_eq!(input.machines[2].act_seq_linalg().unwrap().len(), 2);
+    }
+
     #[test]
     // Test with part 2 examples.
     fn test_set_joltage() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        let joltage_seq = input.machines[0].joltage_seq();
+        let joltage_seq = input.machines[0].joltage_seq().unwrap();
         assert_eq!(joltage_seq.iter().sum::<usize>(), 10);
 
-        let joltage_seq = input.machines[1].joltage_seq();
+        let joltage_seq = input.machines[1].joltage_seq().unwrap();
         assert_eq!(joltage_seq.iter().sum::<usize>(), 12);
 
-        let joltage_seq = input.machines[2].joltage_seq();
+        let joltage_seq = input.machines[2].joltage_seq().unwrap();
         assert_eq!(joltage_seq.iter().sum::<usize>(), 11); 
     }
 
     #[test]
     // Test case with one of my actual inputs
     fn test2_set_joltage() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
-        let joltage_seq = input.machines[0].joltage_seq();
+        let joltage_seq = input.machines[0].joltage_seq().unwrap();
         assert_eq!(joltage_seq.iter().sum::<usize>(), 90);
     }
 
     #[test]
     // Test part 2 with a second actual input
     fn test3_set_joltage() {
-        let input = Input::read(EXAMPLE3);
+        let input = Input::read(EXAMPLE3).unwrap();
 
-        let joltage_seq = input.machines[0].joltage_seq();
+        let joltage_seq = input.machines[0].joltage_seq().unwrap();
         assert_eq!(joltage_seq.iter().sum::<usize>(), 128);
     }
 
@@ -572,6 +837,18 @@ mod test {
         assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(7));
     }
 
+    #[test]
+    // Every registered part 1 strategy agrees on the example.
+    fn test_part1_strategies() {
+        let d = Day10::new();
+        for strategy in d.strategies(1) {
+            assert_eq!(d.solve_with(1, strategy, EXAMPLE1), Ok(Answer::Numeric(7)));
+        }
+        assert_eq!(d.solve_with(1, DEFAULT_STRATEGY, EXAMPLE1), Ok(Answer::Numeric(7)));
+        assert_eq!(d.solve_with(1, "nonesuch", EXAMPLE1),
+                   Err(SolveError::NoStrategy { part: 1, strategy: String::from("nonesuch") }));
+    }
+
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
@@ -579,5 +856,51 @@ mod test {
         let d = Day10::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(33));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Both part 1 strategies agree.
+    fn test_generate() {
+        let d = Day10::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 5).unwrap();
+            assert_eq!(Input::read(&text).unwrap().machines.len(), 5);
+
+            assert_eq!(d.solve_with(1, "bfs", &text), d.solve_with(1, "linalg", &text));
+            assert!(matches!(d.part2(&text), Answer::Numeric(_)));
+        }
+    }
+
+    #[test]
+    // The joltage search gives up, rather than running for ages, with many free buttons.
+    fn test_joltage_search_limit() {
+        let d = Day10::new();
+        let many = format!("[#] {}{{1000}}\n", "(0) ".repeat(16));
+        assert_eq!(d.try_part2(&many),
+                   Err(SolveError::Unsolvable(String::from("Gave up after trying 1000000 sets of 15 free buttons."))));
+        assert_eq!(d.try_part2("[#] (0) (0) (0) {1000}\n"), Ok(Answer::Numeric(1000)));
+    }
+
+    #[test]
+    // Lint flags multi-digit buttons, which the parser would silently skip.
+    fn test_lint() {
+        let d = Day10::new();
+        assert_eq!(d.lint(EXAMPLE1), Some(Vec::new()));
+
+        let bad = "\
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[..........#] (10) (0,2) {1,1,1,1,1,1,1,1,1,1,1}
+[.#.] (0,1) (12,1) {1,1,1}
+";
+        assert_eq!(d.lint(bad), Some(vec![
+            SolveError::parse(2, "Button '(10)' isn't single digits."),
+            SolveError::parse(2, "11 lights, at most 10 allowed."),
+            SolveError::parse(3, "Button '(12,1)' isn't single digits."),
+        ]));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day10::new(), &[EXAMPLE1, EXAMPLE2, EXAMPLE3]);
+    }
 }
@@ -1,6 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 
-use crate::day::{Day, Answer};
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::trace;
+
+const DAY: usize = 11;
 
 // A representation of the puzzle inputs.
 struct Input {
@@ -8,20 +15,138 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut connections = Vec::new();
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
-            let parts: Vec<&str> = line.split(": ").collect();
-            assert_eq!(parts.len(), 2);
-            let name = parts[0].to_string();
-            let connected: Vec<String> = parts[1].split(" ").map(|s| {s.to_string()}).collect();
+            connections.push(Input::parse_line(line_no + 1, line)?);
+        }
+
+        Ok(Input { connections })
+    }
 
-            connections.push((name, connected));
+    // Parse "name: output output ...".  Lines are numbered from 1.
+    fn parse_line(line_no: usize, line: &str) -> Result<(String, Vec<String>), SolveError> {
+        let (name, outputs) = line.split_once(": ")
+            .ok_or_else(|| { SolveError::parse(line_no, format!("Expected 'name: outputs', got '{line}'")) })?;
+        let name = name.to_string();
+        let connected: Vec<String> = outputs.split_whitespace().map(|s| {s.to_string()}).collect();
+
+        Ok((name, connected))
+    }
+
+    // Every line that doesn't parse, device defined twice, output that's never defined, and
+    // device that's on a cycle (or fed by one), which would stop the paths being counted.
+    fn lint(text: &str) -> Vec<SolveError> {
+        let mut problems = Vec::new();
+
+        // Devices by name, with the line each is defined on.
+        let mut devices: HashMap<String, (usize, Vec<String>)> = HashMap::new();
+        for (line_no, line) in text.lines().enumerate() {
+            match Input::parse_line(line_no + 1, line) {
+                Ok((name, outputs)) => match devices.entry(name) {
+                    Entry::Occupied(e) => {
+                        problems.push(SolveError::parse(line_no + 1, format!("Device '{}' is defined twice.", e.key())));
+                    }
+                    Entry::Vacant(e) => {
+                        e.insert((line_no + 1, outputs));
+                    }
+                },
+                Err(e) => problems.push(e),
+            }
         }
 
-        Input { connections }
+        let mut n_inputs: HashMap<&str, usize> = devices.keys().map(|name| { (name.as_str(), 0) }).collect();
+        for (line_no, outputs) in devices.values() {
+            for output in outputs {
+                match n_inputs.get_mut(output.as_str()) {
+                    Some(n) => *n += 1,
+                    None if output == "out" => {}
+                    None => problems.push(SolveError::parse(*line_no, format!("Device '{output}' is never defined."))),
+                }
+            }
+        }
+
+        // Peel off devices with no remaining inputs, as Reactor::new() does.  Any left over are
+        // on a cycle or downstream of one.
+        let mut ready: Vec<&str> = n_inputs.iter().filter(|(_, n)| { **n == 0 }).map(|(name, _)| { *name }).collect();
+        while let Some(name) = ready.pop() {
+            for output in &devices[name].1 {
+                if let Some(n) = n_inputs.get_mut(output.as_str()) {
+                    *n -= 1;
+                    if *n == 0 {
+                        ready.push(output);
+                    }
+                }
+            }
+        }
+        for (name, n) in n_inputs {
+            if n > 0 {
+                problems.push(SolveError::parse(devices[name].0, format!("Device '{name}' is on a cycle, or fed by one.")));
+            }
+        }
+
+        problems.sort_by_key(|p| { p.line() });
+
+        problems
+    }
+
+    // Random input in the format read() accepts: a layered graph of about `size` devices.
+    //
+    // "svr" is in the first layer, "you" in the second, then "fft" and "dac" a third and two
+    // thirds of the way down.  Each device connects to one or two devices in the next layer, and
+    // the last layer connects to "out".  There are at most 50 layers so path counts fit in a usize.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const RESERVED: [&str; 5] = ["svr", "you", "fft", "dac", "out"];
+
+        let n_layers = (size / 4).clamp(4, 50);
+        let mut layers: Vec<Vec<String>> = vec![Vec::new(); n_layers];
+        layers[0].push("svr".to_string());
+        layers[1].push("you".to_string());
+        layers[n_layers / 3].push("fft".to_string());
+        layers[2 * n_layers / 3].push("dac".to_string());
+
+        // Every layer needs a device, then spread the rest at random.
+        let mut names = HashSet::new();
+        let mut new_name = |rng: &mut Rng| {
+            loop {
+                let name: String = (0..3).map(|_| { char::from(b'a' + rng.below(26) as u8) }).collect();
+                if !RESERVED.contains(&name.as_str()) && names.insert(name.clone()) {
+                    return name;
+                }
+            }
+        };
+        for layer in layers.iter_mut().filter(|l| { l.is_empty() }) {
+            layer.push(new_name(rng));
+        }
+        // (Three letter names run out at 26^3, so cap the size well short of that.)
+        for _ in layers.iter().map(|l| { l.len() }).sum::<usize>()..size.min(10_000) {
+            let layer = rng.range(1, n_layers - 1);
+            layers[layer].push(new_name(rng));
+        }
+
+        let mut lines = Vec::new();
+        for (n, layer) in layers.iter().enumerate() {
+            for name in layer {
+                let outputs = if n + 1 == n_layers {
+                    vec!["out".to_string()]
+                }
+                else {
+                    let next = &layers[n+1];
+                    let mut outputs = vec![next[rng.below(next.len())].clone()];
+                    let other = &next[rng.below(next.len())];
+                    if rng.chance(1, 2) && !outputs.contains(other) {
+                        outputs.push(other.clone());
+                    }
+                    outputs
+                };
+                lines.push(format!("{name}: {}\n", outputs.join(" ")));
+            }
+        }
+        rng.shuffle(&mut lines);
+
+        lines.concat()
     }
 }
 
@@ -42,7 +167,7 @@ struct Reactor {
 }
 
 impl Reactor {
-    pub fn new(input: &Input) -> Reactor {
+    pub fn new(input: &Input) -> Result<Reactor, SolveError> {
         // Assign a node id to each node by name
         let mut next_id = 0;
         let mut node_ids: HashMap<String, usize> = HashMap::new();
@@ -52,27 +177,28 @@ impl Reactor {
         next_id += 1;
 
         // Assign ids to remaining nodes
-        input.connections.iter()
-            .for_each(|conn| {
-                node_ids.insert(conn.0.clone(), next_id);
-                next_id += 1;
-            });
+        for (line_no, conn) in input.connections.iter().enumerate() {
+            if node_ids.insert(conn.0.clone(), next_id).is_some() {
+                return Err(SolveError::parse(line_no + 1, format!("Device '{}' is defined twice.", conn.0)));
+            }
+            next_id += 1;
+        }
 
         // Create a vector of Nodes, one for each node id.
         let mut nodes = Vec::new();
         nodes.push(Node::new(0, Vec::new()));
 
-        input.connections.iter()
-            .for_each(|(node_name, output_names)| {
-                let id = node_ids[node_name];
-                let outputs = output_names.iter()
-                    .map(|name| {
-                        node_ids[name]
-                    })
-                    .collect();
-                
-                nodes.push(Node::new(id, outputs));
-            });
+        for (line_no, (node_name, output_names)) in input.connections.iter().enumerate() {
+            let id = node_ids[node_name];
+            let mut outputs = Vec::new();
+            for name in output_names {
+                let output = node_ids.get(name)
+                    .ok_or_else(|| { SolveError::parse(line_no + 1, format!("Device '{name}' is never defined.")) })?;
+                outputs.push(*output);
+            }
+
+            nodes.push(Node::new(id, outputs));
+        }
 
         // Compute the inputs to each node
         // Start with an empty Vec of Vec structure.
@@ -86,45 +212,66 @@ impl Reactor {
             })
         });
 
-        Reactor { node_ids, inputs_to }
+        // Counting paths needs the connections to form a DAG.  Peel off nodes with no remaining
+        // inputs; any left over are on a cycle.
+        let mut n_inputs: Vec<usize> = inputs_to.iter().map(|inputs| { inputs.len() }).collect();
+        let mut ready: Vec<usize> = (0..nodes.len()).filter(|id| { n_inputs[*id] == 0 }).collect();
+        let mut peeled = 0;
+        while let Some(id) = ready.pop() {
+            peeled += 1;
+            for output in &nodes[id].outputs {
+                n_inputs[*output] -= 1;
+                if n_inputs[*output] == 0 {
+                    ready.push(*output);
+                }
+            }
+        }
+        if peeled < nodes.len() {
+            return Err(SolveError::Unsolvable(String::from("The connections form a cycle.")));
+        }
+
+        Ok(Reactor { node_ids, inputs_to })
+    }
+
+    fn node_id(&self, name: &str) -> Result<usize, SolveError> {
+        self.node_ids.get(name).copied()
+            .ok_or_else(|| { SolveError::Unsolvable(format!("There is no device '{name}'.")) })
     }
 
-    fn num_paths_to(&self, from: usize, to: usize, paths_to_cache: &mut HashMap<usize, usize>) -> usize {
+    fn num_paths_to<T: Arith>(&self, from: usize, to: usize, paths_to_cache: &mut HashMap<usize, T>) -> Result<T, SolveError> {
         let num = if to == from {
-            1
+            T::from_usize(1)
             // println!("Paths to {to} : {num} (degenerate case)");
         }
         else if paths_to_cache.contains_key(&to) {
-            paths_to_cache[&to]
+            paths_to_cache[&to].clone()
             // println!("Paths to {to} : {num} (from cache)");
         }
         else {
-            let num = self.inputs_to[to].iter().map(|&in_node| {
-                      self.num_paths_to(from, in_node, paths_to_cache)
-                  })
-                  .sum();
+            let mut num = T::from_usize(0);
+            for &in_node in &self.inputs_to[to] {
+                num = num.checked_add(&self.num_paths_to(from, in_node, paths_to_cache)?)
+                    .ok_or(SolveError::overflow(DAY, "path count"))?;
+            }
 
             // Add to the cache.
-            paths_to_cache.insert(to, num);
+            paths_to_cache.insert(to, num.clone());
+            trace::note(|| { format!("Paths to {to} : {num} (added to cache)") });
 
             num
-
-            // println!("Paths to {to} : {num} (added to cache)");
         };
 
-        num
+        Ok(num)
     }
 
-    pub fn num_paths_p1(&self) -> usize {
-        let mut paths_to_cache: HashMap<usize, usize> = HashMap::new();
-
-        self.num_paths_to(self.node_ids["you"], self.node_ids["out"], &mut paths_to_cache)
+    pub fn num_paths_p1<T: Arith>(&self) -> Result<T, SolveError> {
+        self.num_paths_between("you", "out")
     }
 
-    pub fn num_paths_between(&self, from: &str, to: &str) -> usize {
-        let mut paths_to_cache: HashMap<usize, usize> = HashMap::new();
+    pub fn num_paths_between<T: Arith>(&self, from: &str, to: &str) -> Result<T, SolveError> {
+        let mut paths_to_cache: HashMap<usize, T> = HashMap::new();
 
-        self.num_paths_to(self.node_ids[from], self.node_ids[to], &mut paths_to_cache) 
+        self.num_paths_to(self.node_id(from)?, self.node_id(to)?, &mut paths_to_cache) 
     }
 }
 
@@ -135,44 +282,84 @@ impl Day11 {
     pub const fn new() -> Self {
         Self { }
     }
+
+    // Paths from svr to out through both dac and fft, in either order.
+    fn num_paths_p2<T: Arith>(reactor: &Reactor) -> Result<Answer, SolveError> {
+        let svr_dac: T = reactor.num_paths_between("svr", "dac")?;
+        let dac_fft: T = reactor.num_paths_between("dac", "fft")?;
+        let fft_out: T = reactor.num_paths_between("fft", "out")?;
+
+        let svr_fft: T = reactor.num_paths_between("svr", "fft")?;
+        let fft_dac: T = reactor.num_paths_between("fft", "dac")?;
+        let dac_out: T = reactor.num_paths_between("dac", "out")?;
+
+        let product = |a: T, b: T, c: T| { a.checked_mul(&b)?.checked_mul(&c) };
+        let paths = product(svr_dac, dac_fft, fft_out)
+            .zip(product(svr_fft, fft_dac, dac_out))
+            .and_then(|(a, b)| { a.checked_add(&b) })
+            .ok_or(SolveError::overflow(DAY, "path count"))?;
+
+        Ok(paths.into_answer())
+    }
 }
 
 impl Day for Day11 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
-        let reactor = Reactor::new(&input);
+        let input = Input::read(text)?;
+        let reactor = Reactor::new(&input)?;
 
-        Answer::Numeric(reactor.num_paths_p1())
+        arith::widen(|| { Ok(reactor.num_paths_p1::<usize>()?.into_answer()) },
+                     || { Ok(reactor.num_paths_p1::<u128>()?.into_answer()) },
+                     || { Ok(reactor.num_paths_p1::<BigUint>()?.into_answer()) })
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);
-        let reactor = Reactor::new(&input);
+        let input = Input::read(text)?;
+        let reactor = Reactor::new(&input)?;
 
-        let svr_dac = reactor.num_paths_between("svr", "dac");
-        let dac_fft = reactor.num_paths_between("dac", "fft");
-        let fft_out = reactor.num_paths_between("fft", "out");
+        arith::widen(|| { Day11::num_paths_p2::<usize>(&reactor) },
+                     || { Day11::num_paths_p2::<u128>(&reactor) },
+                     || { Day11::num_paths_p2::<BigUint>(&reactor) })
+    }
 
-        let svr_fft = reactor.num_paths_between("svr", "fft");
-        let fft_dac = reactor.num_paths_between("fft", "dac");
-        let dac_out = reactor.num_paths_between("dac", "out");
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        Some(Input::lint(text))
+    }
 
-        let paths = svr_dac * dac_fft * fft_out +
-                    svr_fft * fft_dac * dac_out;
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // The reactor's node ids, each with the ids feeding it.
+    fn inspect(&self, text: &str) -> Option<Result<String, SolveError>> {
+        let reactor = match Input::read(text).and_then(|input| { Reactor::new(&input) }) {
+            Ok(reactor) => reactor,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut names: Vec<(&usize, &String)> = reactor.node_ids.iter().map(|(name, id)| { (id, name) }).collect();
+        names.sort();
+        let mut text = format!("{} devices (id name <- inputs)\n", names.len());
+        for (id, name) in names {
+            let inputs: Vec<String> = reactor.inputs_to[*id].iter().map(|i| { i.to_string() }).collect();
+            text.push_str(&format!("  {id:4} {name} <- {}\n", inputs.join(" ")));
+        }
 
-        Answer::Numeric(paths)
+        Some(Ok(text))
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day11::{Day11, Input, Reactor};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 aaa: you hhh
@@ -206,7 +393,7 @@ hhh: out
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.connections.len(), 10);
         assert_eq!(input.connections[0].0, "aaa");
@@ -217,7 +404,7 @@ hhh: out
 
     #[test]
     fn test_read_part2() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
         assert_eq!(input.connections.len(), 13);
         assert_eq!(input.connections[0].0, "svr");
@@ -228,34 +415,44 @@ hhh: out
 
     #[test]
     fn test_create_reactor() {
-        let input = Input::read(EXAMPLE1);
-        let reactor = Reactor::new(&input);
+        let input = Input::read(EXAMPLE1).unwrap();
+        let reactor = Reactor::new(&input).unwrap();
 
         assert_eq!(reactor.inputs_to.len(), 11);
         assert_eq!(reactor.node_ids.len(), 11);
     }
 
     
+    #[test]
+    // Ids are given in input order, after "out".
+    fn test_inspect() {
+        let d = Day11::new();
+        let text = d.inspect(EXAMPLE1).unwrap().unwrap();
+        assert!(text.starts_with("11 devices (id name <- inputs)\n     0 out <- "));
+        assert!(text.contains("\n     1 aaa <- \n"));
+        assert!(d.inspect("aaa: bbb\n").unwrap().is_err());
+    }
+
     #[test]
     fn test_num_paths_p1() {
-        let input = Input::read(EXAMPLE1);
-        let reactor = Reactor::new(&input);
+        let input = Input::read(EXAMPLE1).unwrap();
+        let reactor = Reactor::new(&input).unwrap();
 
-        assert_eq!(reactor.num_paths_p1(), 5);
+        assert_eq!(reactor.num_paths_p1::<usize>(), Ok(5));
     }
 
     #[test]
     fn test_num_paths_p2() {
-        let input = Input::read(EXAMPLE2);
-        let reactor = Reactor::new(&input);
+        let input = Input::read(EXAMPLE2).unwrap();
+        let reactor = Reactor::new(&input).unwrap();
 
-        let svr_dac = reactor.num_paths_between("svr", "dac");
-        let dac_fft = reactor.num_paths_between("dac", "fft");
-        let fft_out = reactor.num_paths_between("fft", "out");
+        let svr_dac = reactor.num_paths_between::<usize>("svr", "dac").unwrap();
+        let dac_fft = reactor.num_paths_between::<usize>("dac", "fft").unwrap();
+        let fft_out = reactor.num_paths_between::<usize>("fft", "out").unwrap();
 
-        let svr_fft = reactor.num_paths_between("svr", "fft");
-        let fft_dac = reactor.num_paths_between("fft", "dac");
-        let dac_out = reactor.num_paths_between("dac", "out");
+        let svr_fft = reactor.num_paths_between::<usize>("svr", "fft").unwrap();
+        let fft_dac = reactor.num_paths_between::<usize>("fft", "dac").unwrap();
+        let dac_out = reactor.num_paths_between::<usize>("dac", "out").unwrap();
 
         let paths = svr_dac * dac_fft * fft_out +
                     svr_fft * fft_dac * dac_out;
@@ -277,5 +474,47 @@ hhh: out
         let d = Day11::new();
         assert_eq!(d.part2(EXAMPLE2), Answer::Numeric(2));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Every device has a path to "out".
+    fn test_generate() {
+        let d = Day11::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 40).unwrap();
+            assert_eq!(Input::read(&text).unwrap().connections.len(), 40);
+
+            assert!(matches!(d.part1(&text), Answer::Numeric(n) if n > 0));
+            assert!(matches!(d.part2(&text), Answer::Numeric(_)));
+        }
+    }
+
+    #[test]
+    // Lint reports every problem, where Reactor::new() stops at the first.
+    fn test_lint() {
+        let d = Day11::new();
+        assert_eq!(d.lint(EXAMPLE1), Some(Vec::new()));
+
+        let bad = "\
+you: aaa bbb
+aaa: bbb
+bbb: ccc
+ccc: bbb out
+aaa: out
+ddd eee
+eee: nowhere
+";
+        assert_eq!(d.lint(bad), Some(vec![
+            SolveError::parse(3, "Device 'bbb' is on a cycle, or fed by one."),
+            SolveError::parse(4, "Device 'ccc' is on a cycle, or fed by one."),
+            SolveError::parse(5, "Device 'aaa' is defined twice."),
+            SolveError::parse(6, "Expected 'name: outputs', got 'ddd eee'"),
+            SolveError::parse(7, "Device 'nowhere' is never defined."),
+        ]));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day11::new(), &[EXAMPLE1, EXAMPLE2]);
+    }
 }
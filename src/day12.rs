@@ -1,6 +1,10 @@
 use regex::Regex;
 
-use crate::day::{Day, Answer};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::vis::{Style, Svg, PALETTE};
+
+const DAY: usize = 12;
 
 // One piece to fit on the board
 #[derive(Clone)]
@@ -38,28 +42,83 @@ struct Board {
     width: usize,
     height: usize,
     counts: Vec<usize>,
+    line_no: usize,  // the line it's defined on, from 1
 }
 
 impl Board {
-    fn is_impossible(&self, pieces: &Vec<Piece>) -> bool {
-        let board_area = self.height * self.width;
-
-        let pieces_area: usize = self.counts.iter()
-            .enumerate()
-            .map(|(index, count)| {
-                pieces[index].coverage.len() * count
-            })
-            .sum();
+    fn is_impossible(&self, pieces: &[Piece]) -> Result<bool, SolveError> {
+        let board_area = self.height.checked_mul(self.width).ok_or(SolveError::overflow(DAY, "board area"))?;
+
+        let mut pieces_area: usize = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            pieces_area = pieces[index].coverage.len().checked_mul(*count)
+                .and_then(|area| { pieces_area.checked_add(area) })
+                .ok_or(SolveError::overflow(DAY, "area of pieces"))?;
+        }
 
-        pieces_area > board_area
+        Ok(pieces_area > board_area)
     }
 
-    fn is_trivial(&self, piece_w: usize, piece_h: usize) -> bool {
+    fn is_trivial(&self, piece_w: usize, piece_h: usize) -> Result<bool, SolveError> {
         let pieces_h = self.height / piece_h;
         let pieces_w = self.width / piece_w;
-        let easy_fit_pieces = pieces_h * pieces_w;
+        let easy_fit_pieces = pieces_h.checked_mul(pieces_w).ok_or(SolveError::overflow(DAY, "board area"))?;
+
+        let n_pieces = self.counts.iter().try_fold(0_usize, |acc, count| { acc.checked_add(*count) })
+            .ok_or(SolveError::overflow(DAY, "count of pieces"))?;
 
-        easy_fit_pieces >= self.counts.iter().sum()
+        Ok(easy_fit_pieces >= n_pieces)
+    }
+
+    // The board with its pieces placed, when it's trivially solvable: each piece in its own
+    // 3x3 cell.  Otherwise just the board, saying why it's empty.
+    fn draw(&self, pieces: &[Piece], style: Style) -> Result<String, SolveError> {
+        const CELL: f64 = 10.0;
+
+        let (verdict, placed) = if self.is_trivial(3, 3)? {
+            let per_row = self.width / 3;
+            let ids = self.counts.iter().enumerate().flat_map(|(id, count)| { std::iter::repeat_n(id, *count) });
+            let placed: Vec<(usize, usize, usize)> = ids.enumerate()
+                .flat_map(|(n, id)| {
+                    let (x0, y0) = (3 * (n % per_row), 3 * (n / per_row));
+                    pieces[id].coverage.iter().map(move |(row, col)| { (x0 + col, y0 + row, id) })
+                })
+                .collect();
+            ("trivially solvable", placed)
+        }
+        else if self.is_impossible(pieces)? {
+            ("impossible, the pieces cover more than the board", Vec::new())
+        }
+        else {
+            ("not solved", Vec::new())
+        };
+        let caption = format!("{}x{}: {verdict}", self.width, self.height);
+
+        match style {
+            Style::Ascii => {
+                let mut grid = vec![vec!['.'; self.width]; self.height];
+                for (x, y, id) in placed {
+                    grid[y][x] = char::from_digit(id as u32, 10).unwrap_or('#');
+                }
+                let mut text = format!("{caption}\n");
+                for row in grid {
+                    text.extend(row);
+                    text.push('\n');
+                }
+
+                Ok(text)
+            }
+            Style::Svg => {
+                let mut svg = Svg::new(self.width as f64 * CELL, self.height as f64 * CELL + 20.0);
+                svg.text(0.0, 14.0, 14.0, &caption);
+                svg.rect(0.0, 20.0, self.width as f64 * CELL, self.height as f64 * CELL, "#f6f8fa");
+                for (x, y, id) in placed {
+                    svg.rect(x as f64 * CELL, y as f64 * CELL + 20.0, CELL, CELL, PALETTE[id % PALETTE.len()]);
+                }
+
+                Ok(svg.finish())
+            }
+        }
     }
 }
 
@@ -70,7 +129,7 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         // matches the line that starts a piece def.  e.g. "3:".
         // match[1] contains the digit.
@@ -91,11 +150,15 @@ impl Input {
         let mut piece_in_progress = Piece::default() ;
         let mut next_row = 0;
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
+            let number = |s: &str| {
+                s.parse::<usize>().map_err(|_| { SolveError::parse(line_no + 1, format!("Bad number '{s}'")) })
+            };
+
             // Process one line
             if let Some(matches) = piece_start_re.captures(line) {
                 // Start of a new piece
-                piece_in_progress.set_id(matches[1].parse::<usize>().unwrap());
+                piece_in_progress.set_id(number(&matches[1])?);
             }
             else if let Some(matches) = piece_row_re.captures(line) {
                 // A new row of a piece
@@ -110,16 +173,98 @@ impl Input {
             }
             else if let Some(matches) = board_def_re.captures(line) {
                 // A board definition
-                let counts = matches[3].split(" ").map(|s| {s.parse::<usize>().unwrap()}).collect();
-                let width = matches[1].parse::<usize>().unwrap();
-                let height = matches[2].parse::<usize>().unwrap();
-                let board = Board { width, height, counts };
+                let counts = matches[3].split(" ").map(number).collect::<Result<Vec<usize>, SolveError>>()?;
+                if counts.len() > pieces.len() {
+                    return Err(SolveError::parse(line_no + 1, format!("{} counts for {} pieces.", counts.len(), pieces.len())));
+                }
+                let width = number(&matches[1])?;
+                let height = number(&matches[2])?;
+                let board = Board { width, height, counts, line_no: line_no + 1 };
                 boards.push(board);
             }
+            else {
+                return Err(SolveError::parse(line_no + 1, format!("Couldn't parse '{line}'")));
+            }
 
         }
 
-        Input { pieces, boards }
+        Ok(Input { pieces, boards })
+    }
+
+    // The first line read() rejects, or every board part 1 can't handle: those that are neither
+    // trivially solvable nor impossible.
+    fn lint(text: &str) -> Vec<SolveError> {
+        let input = match Input::read(text) {
+            Ok(input) => input,
+            Err(e) => return vec![e],
+        };
+
+        let mut problems = Vec::new();
+        for b in &input.boards {
+            match (b.is_trivial(3, 3), b.is_impossible(&input.pieces)) {
+                (Ok(false), Ok(false)) => {
+                    problems.push(SolveError::parse(b.line_no,
+                        format!("Board {}x{} is neither trivially solvable nor impossible.", b.width, b.height)));
+                }
+                (Err(e), _) | (_, Err(e)) => problems.push(e),
+                _ => {}
+            }
+        }
+
+        problems
+    }
+
+    // Random input in the format read() accepts: six 3x3 pieces, then `size` boards.
+    //
+    // Part 1 only handles boards that are trivially solvable (every piece gets its own 3x3 cell)
+    // or impossible (the pieces cover more area than the board), so each board is one or the other.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        let mut areas = Vec::new();
+        for id in 0..6 {
+            text.push_str(&format!("{id}:\n"));
+            let mut area = 0;
+            for row in 0..3 {
+                for col in 0..3 {
+                    // Always cover the middle so no piece is empty.
+                    if (row == 1 && col == 1) || rng.chance(2, 3) {
+                        text.push('#');
+                        area += 1;
+                    }
+                    else {
+                        text.push('.');
+                    }
+                }
+                text.push('\n');
+            }
+            text.push('\n');
+            areas.push(area);
+        }
+
+        for _ in 0..size {
+            let width = rng.range(3, 50);
+            let height = rng.range(3, 50);
+            let mut counts = [0; 6];
+            if rng.chance(1, 2) {
+                // Trivial: no more pieces than 3x3 cells.
+                for _ in 0..rng.range(0, (width / 3) * (height / 3)) {
+                    counts[rng.below(6)] += 1;
+                }
+            }
+            else {
+                // Impossible: keep adding pieces until they cover more than the board.
+                let mut area = 0;
+                while area <= width * height {
+                    let piece = rng.below(6);
+                    counts[piece] += 1;
+                    area += areas[piece];
+                }
+            }
+            let counts: Vec<String> = counts.iter().map(|c| { c.to_string() }).collect();
+            text.push_str(&format!("{width}x{height}: {}\n", counts.join(" ")));
+        }
+
+        text
     }
 }
 
@@ -135,39 +280,60 @@ impl Day12 {
 impl Day for Day12 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let _input = Input::read(text);
-
-        let trivial_fits = _input.boards.iter()
-            .filter(|b| {
-                b.is_trivial(3, 3)
-            })
-            .count();
+        let _input = Input::read(text)?;
 
-        let impossible = _input.boards.iter()
-            .filter(|b| {
-                b.is_impossible(&_input.pieces)
-            })
-            .count();
+        let mut trivial_fits = 0;
+        let mut impossible = 0;
+        for b in _input.boards.iter() {
+            if b.is_trivial(3, 3)? {
+                trivial_fits += 1;
+            }
+            if b.is_impossible(&_input.pieces)? {
+                impossible += 1;
+            }
+        }
 
         // This relies on the assumption that all solutions are trivial solutions.
-        // Assert that we've accounted for all the boards between trivial solutions and impossible ones.
-        assert_eq!(_input.boards.len(), trivial_fits+impossible);
+        // Check that we've accounted for all the boards between trivial solutions and impossible ones.
+        if _input.boards.len() != trivial_fits+impossible {
+            return Err(SolveError::Unsolvable(String::from("Some boards are neither trivial nor impossible.")));
+        }
 
-        Answer::Numeric(trivial_fits)
+        Ok(Answer::Numeric(trivial_fits))
     }
 
-    fn part2(&self, _text: &str) -> Answer {
+    fn try_part2(&self, _text: &str) -> Result<Answer, SolveError> {
         // There is no part 2 for Day 12
-        Answer::None
+        Ok(Answer::None)
+    }
+
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        Some(Input::lint(text))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // Each board in turn.
+    fn visualize(&self, text: &str, style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        let frames = Input::read(text).and_then(|input| {
+            input.boards.iter().map(|b| { b.draw(&input.pieces, style) }).collect()
+        });
+
+        Some(frames)
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day12::{Day12, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
+    use crate::vis::Style;
     
     const EXAMPLE1: &str = "\
 0:
@@ -245,7 +411,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.pieces.len(), 6);
         assert_eq!(input.pieces[0].coverage, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (2, 0), (2, 1)]);
@@ -259,32 +425,46 @@ mod test {
 
     #[test]
     fn test_area_fits() {
-        let input = Input::read(EXAMPLE1);
-        let input2 = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE1).unwrap();
+        let input2 = Input::read(EXAMPLE2).unwrap();
 
-        assert_eq!(input.boards[0].is_impossible(&input.pieces), false);
-        assert_eq!(input.boards[1].is_impossible(&input.pieces), false);    
-        assert_eq!(input.boards[2].is_impossible(&input.pieces), false);
+        assert_eq!(input.boards[0].is_impossible(&input.pieces), Ok(false));
+        assert_eq!(input.boards[1].is_impossible(&input.pieces), Ok(false));    
+        assert_eq!(input.boards[2].is_impossible(&input.pieces), Ok(false));
 
-        assert_eq!(input2.boards[0].is_impossible(&input.pieces), true);
-        assert_eq!(input2.boards[1].is_impossible(&input.pieces), true);
-        assert_eq!(input2.boards[2].is_impossible(&input.pieces), true);
-        assert_eq!(input2.boards[3].is_impossible(&input.pieces), false);
+        assert_eq!(input2.boards[0].is_impossible(&input.pieces), Ok(true));
+        assert_eq!(input2.boards[1].is_impossible(&input.pieces), Ok(true));
+        assert_eq!(input2.boards[2].is_impossible(&input.pieces), Ok(true));
+        assert_eq!(input2.boards[3].is_impossible(&input.pieces), Ok(false));
     }
 
     #[test]
     fn test_is_trivial() {
-        let input = Input::read(EXAMPLE1);
-        let input2 = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE1).unwrap();
+        let input2 = Input::read(EXAMPLE2).unwrap();
 
-        assert_eq!(input.boards[0].is_trivial(3, 3), false);
-        assert_eq!(input.boards[1].is_trivial(3, 3), false);    
-        assert_eq!(input.boards[2].is_trivial(3, 3), false);
+        assert_eq!(input.boards[0].is_trivial(3, 3), Ok(false));
+        assert_eq!(input.boards[1].is_trivial(3, 3), Ok(false));    
+        assert_eq!(input.boards[2].is_trivial(3, 3), Ok(false));
 
-        assert_eq!(input2.boards[0].is_trivial(3, 3), false);
-        assert_eq!(input2.boards[1].is_trivial(3, 3), false);
-        assert_eq!(input2.boards[2].is_trivial(3, 3), false);
-        assert_eq!(input2.boards[3].is_trivial(3, 3), true);
+        assert_eq!(input2.boards[0].is_trivial(3, 3), Ok(false));
+        assert_eq!(input2.boards[1].is_trivial(3, 3), Ok(false));
+        assert_eq!(input2.boards[2].is_trivial(3, 3), Ok(false));
+        assert_eq!(input2.boards[3].is_trivial(3, 3), Ok(true));
+    }
+
+    #[test]
+    // Trivial boards get their pieces drawn, one per 3x3 cell.  The rest are left empty.
+    fn test_visualize() {
+        let d = Day12::new();
+        let frames = d.visualize(EXAMPLE2, Style::Ascii).unwrap().unwrap();
+        assert_eq!(frames.len(), 4);
+        assert!(frames[0].starts_with("37x39: impossible, the pieces cover more than the board\n....."));
+        assert!(frames[3].starts_with("42x45: trivially solvable\n0..0..0..0..0..0..0..0..0..0..0..0..0..0..\n00.00.00."));
+        assert_eq!(frames[3].lines().skip(1).map(|line| { line.matches('5').count() }).sum::<usize>(), 37 * 7);
+
+        let svg = d.visualize(EXAMPLE2, Style::Svg).unwrap().unwrap();
+        assert!(svg[3].contains("<text x=\"0\" y=\"14\" font-size=\"14\" font-family=\"sans-serif\">42x45: trivially solvable</text>"));
     }
 
     #[test]
@@ -302,5 +482,38 @@ mod test {
         let d = Day12::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::None);
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Every board is trivial or impossible, so part 1's
+    // assertion holds.
+    fn test_generate() {
+        let d = Day12::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!(input.pieces.len(), 6);
+            assert_eq!(input.boards.len(), 20);
+
+            assert!(matches!(d.part1(&text), Answer::Numeric(n) if n <= 20));
+        }
+    }
+
+    #[test]
+    // Part 1 can't handle any of the first example's boards, but can handle all of the second's.
+    fn test_lint() {
+        let d = Day12::new();
+        let neither = "is neither trivially solvable nor impossible.";
+        assert_eq!(d.lint(EXAMPLE1), Some(vec![
+            SolveError::parse(31, format!("Board 4x4 {neither}")),
+            SolveError::parse(32, format!("Board 12x5 {neither}")),
+            SolveError::parse(33, format!("Board 12x5 {neither}")),
+        ]));
+        assert_eq!(d.lint(EXAMPLE2), Some(Vec::new()));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day12::new(), &[EXAMPLE1, EXAMPLE2]);
+    }
 }
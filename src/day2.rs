@@ -1,282 +1,1276 @@
-use std::{cmp::{max, min}, collections::HashSet};
+use std::{cmp::{max, min, Reverse}, collections::{BinaryHeap, HashMap}, io::BufRead};
 
-use crate::day::{Day, Answer};
+use num_bigint::BigUint;
+use regex_automata::{dfa::{dense, Automaton}, util::{primitives::StateID, start}, Anchored};
 
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError, for_each_record, DEFAULT_STRATEGY};
+use crate::rng::Rng;
+use crate::trace;
+
+const DAY: usize = 2;
+
+// A range of ids.  Ids can have any number of digits.
 struct Range {
-    low: usize,
-    high: usize,
+    low: BigUint,
+    high: BigUint,
+}
+
+// Which ids are invalid: those whose digits, in `base`, are a kernel repeated some number of
+// times from `min_repeats` to `max_repeats`.  When `exact`, the kernel has to be the shortest
+// one, so 1111 is 1 repeated four times and not 11 twice.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rule {
+    base: u32,
+    min_repeats: usize,
+    max_repeats: usize,
+    exact: bool,
+}
+
+impl Rule {
+    // Kernels repeated exactly twice.
+    pub const PART1: Rule = Rule { base: 10, min_repeats: 2, max_repeats: 2, exact: false };
+
+    // Kernels repeated twice or more.
+    pub const PART2: Rule = Rule { base: 10, min_repeats: 2, max_repeats: usize::MAX, exact: false };
+
+    // A rule for bases 2 to 36, and at least two repeats.
+    pub fn new(base: u32, min_repeats: usize, max_repeats: usize, exact: bool) -> Result<Rule, SolveError> {
+        check_base(base)?;
+        if min_repeats < 2 || max_repeats < min_repeats {
+            return Err(SolveError::Unsolvable(format!("Repeats {min_repeats} to {max_repeats} need to be two or more, smallest first.")));
+        }
+
+        Ok(Rule { base, min_repeats, max_repeats, exact })
+    }
+
+    fn allows(&self, repeats: usize) -> bool {
+        (self.min_repeats..=self.max_repeats).contains(&repeats)
+    }
+
+    // The allowed repeat counts for ids of `digits` digits.
+    fn repeats(&self, digits: usize) -> impl Iterator<Item = usize> {
+        (2..=digits).filter(move |r| { digits.is_multiple_of(*r) && self.allows(*r) })
+    }
+
+    // How many digits n has in this rule's base.
+    fn digits(&self, n: &BigUint) -> usize {
+        n.to_str_radix(self.base).len()
+    }
+
+    // base^n, if it fits.
+    fn pow<T: Arith>(&self, n: usize) -> Option<T> {
+        let base = T::from_usize(self.base as usize);
+        (0..n).try_fold(T::from_usize(1), |p, _| { p.checked_mul(&base) })
+    }
+
+    // Inclusion-exclusion coefficients for ids of `digits` digits, by repeat count.
+    //
+    // An id whose kernel repeats r times and also s times has a kernel repeating lcm(r, s) times,
+    // so counting the ids for every allowed repeat count once is a signed sum of the counts for
+    // the lcms of sets of them.  With every count of two or more allowed, these are the Mobius
+    // function's: -mu(m) for each repeat count m.  When exact, the ids whose shortest kernel
+    // repeats r times are, by Mobius inversion, mu(m / r) times those repeating m times, for
+    // each m that r divides.
+    fn coefficients(&self, digits: usize) -> Vec<(usize, i64)> {
+        let mut terms: HashMap<usize, i64> = HashMap::new();
+        if self.exact {
+            for r in self.repeats(digits) {
+                for m in (r..=digits).step_by(r).filter(|m| { digits.is_multiple_of(*m) }) {
+                    *terms.entry(m).or_insert(0) += mobius(m / r);
+                }
+            }
+        }
+        else {
+            terms.insert(1, -1);
+            for r in self.repeats(digits) {
+                let before: Vec<(usize, i64)> = terms.iter().map(|(m, c)| { (*m, *c) }).collect();
+                for (m, c) in before {
+                    *terms.entry(lcm(m, r)).or_insert(0) -= c;
+                }
+            }
+        }
+
+        let mut coefficients: Vec<(usize, i64)> = terms.into_iter()
+            .filter(|(m, c)| { *m > 1 && *c != 0 })
+            .collect();
+        coefficients.sort();
+
+        coefficients
+    }
+
+    // Is this id invalid?  Worked out from its digits.
+    fn is_invalid(&self, id: &BigUint) -> bool {
+        let digits = id.to_str_radix(self.base);
+        let n = digits.len();
+
+        // The most times a kernel repeats: its shortest kernel's.  It also repeats any number of
+        // times that divides this.
+        let most = (1..=n).rev()
+            .filter(|r| { n.is_multiple_of(*r) })
+            .find(|r| { digits[..n / r].repeat(*r) == digits })
+            .unwrap_or(1);
+
+        if self.exact {
+            self.allows(most)
+        }
+        else {
+            (2..=most).any(|r| { most.is_multiple_of(r) && self.allows(r) })
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let repeats = match (self.min_repeats, self.max_repeats) {
+            (min, max) if min == max => format!("{min}"),
+            (min, usize::MAX) => format!("{min} or more"),
+            (min, max) => format!("{min} to {max}"),
+        };
+        let exact = if self.exact { ", shortest kernel" } else { "" };
+
+        write!(f, "base {}, {repeats} repeats{exact}", self.base)
+    }
+}
+
+// The number of invalid (or matching) ids in a range, and their sum.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Tally<T> {
+    pub count: T,
+    pub sum: T,
+}
+
+impl<T: Arith> Tally<T> {
+    fn zero() -> Tally<T> {
+        Tally { count: T::from_usize(0), sum: T::from_usize(0) }
+    }
+
+    fn into_answers(self) -> (Answer, Answer) {
+        (self.count.into_answer(), self.sum.into_answer())
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+// The Mobius function: 0 if n has a square factor, otherwise -1 or 1 for an odd or even number
+// of prime factors.
+fn mobius(mut n: usize) -> i64 {
+    let mut mu = 1;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            mu = -mu;
+        }
+        p += 1;
+    }
+
+    if n > 1 { -mu } else { mu }
+}
+
+// Any other way of picking out ids, for counting and summing them over ranges.  Implementations
+// count up to a bound digit by digit rather than testing every id.
+pub trait Predicate: std::fmt::Display {
+    // Does this id match?
+    fn matches(&self, id: &BigUint) -> bool;
+
+    // The count and sum of the matching ids from 1 to `high`.
+    fn tally_to(&self, high: &BigUint) -> Result<Tally<BigUint>, SolveError>;
+}
+
+impl Predicate for Rule {
+    fn matches(&self, id: &BigUint) -> bool {
+        self.is_invalid(id)
+    }
+
+    fn tally_to(&self, high: &BigUint) -> Result<Tally<BigUint>, SolveError> {
+        Range { low: BigUint::from(1_usize), high: high.clone() }.tally(self)
+    }
+}
+
+fn check_base(base: u32) -> Result<(), SolveError> {
+    if !(2..=36).contains(&base) {
+        return Err(SolveError::Unsolvable(format!("Base {base} isn't from 2 to 36.")));
+    }
+
+    Ok(())
+}
+
+// An id's digits, most significant first.
+fn digits_of(id: &BigUint, base: u32) -> Vec<u32> {
+    id.to_radix_be(base).into_iter().map(u32::from).collect()
+}
+
+// Reads an id's digits, most significant first, and says whether it matches at the end.
+trait Machine {
+    type State: Clone + Eq + std::hash::Hash;
+
+    fn start(&self) -> Self::State;
+
+    // The state after another digit, or None if nothing starting this way can match.
+    fn step(&self, state: &Self::State, digit: u32) -> Option<Self::State>;
+
+    fn accepts(&self, state: &Self::State) -> bool;
+}
+
+// The count and sum of the ids from 1 to `high` a machine accepts, by how many ids of each
+// length reach each state.  Ids shorter than `high`, or below it at some digit, can have any
+// digits after that; the ids that are still equal to `high` are followed one digit at a time.
+fn tally_machine<M: Machine>(machine: &M, base: u32, high: &BigUint) -> Tally<BigUint> {
+    let zero = BigUint::from(0_usize);
+    let mut below: HashMap<M::State, (BigUint, BigUint)> = HashMap::new();
+    let mut equal = Some((machine.start(), zero.clone()));
+
+    let bound = digits_of(high, base);
+    for (i, &top) in bound.iter().enumerate() {
+        let mut next: HashMap<M::State, (BigUint, BigUint)> = HashMap::new();
+        let mut add = |state: Option<M::State>, count: &BigUint, sum: BigUint| {
+            if let Some(state) = state {
+                let entry = next.entry(state).or_insert((zero.clone(), zero.clone()));
+                entry.0 += count;
+                entry.1 += sum;
+            }
+        };
+
+        for (state, (count, sum)) in &below {
+            for d in 0..base {
+                add(machine.step(state, d), count, sum * base + count * d);
+            }
+        }
+
+        // Ids shorter than `high` start here.
+        if i > 0 {
+            for d in 1..base {
+                add(machine.step(&machine.start(), d), &BigUint::from(1_usize), BigUint::from(d));
+            }
+        }
+
+        if let Some((state, value)) = equal {
+            let first = if i == 0 { 1 } else { 0 };
+            for d in first..top {
+                add(machine.step(&state, d), &BigUint::from(1_usize), &value * base + d);
+            }
+            equal = machine.step(&state, top).map(|state| { (state, value * base + top) });
+        }
+
+        below = next;
+    }
+
+    let mut tally = Tally { count: zero.clone(), sum: zero };
+    for (state, (count, sum)) in below {
+        if machine.accepts(&state) {
+            tally.count += count;
+            tally.sum += sum;
+        }
+    }
+    if let Some((state, value)) = equal
+        && machine.accepts(&state) && value > BigUint::from(0_usize) {
+        tally.count += 1_usize;
+        tally.sum += value;
+    }
+
+    tally
+}
+
+// Ids that read the same backwards.
+pub struct Palindromes {
+    base: u32,
+}
+
+impl Palindromes {
+    pub fn new(base: u32) -> Result<Palindromes, SolveError> {
+        check_base(base)?;
+
+        Ok(Palindromes { base })
+    }
+
+    // A palindrome of `length` digits is set by its first half, rounded up, so it's the sum of
+    // those digits times these weights: each digit counts at its place and at its mirror's.
+    fn weights(&self, length: usize) -> Vec<BigUint> {
+        let base = BigUint::from(self.base);
+        (0..length.div_ceil(2))
+            .map(|i| {
+                let mirror = length - 1 - i;
+                if mirror == i { base.pow(i as u32) } else { base.pow(mirror as u32) + base.pow(i as u32) }
+            })
+            .collect()
+    }
+
+    // The count and sum of the palindromes whose first halves (no leading zero) are below
+    // `bound`, as digits.  Past the first place they go below it, the digits are free.
+    fn below(&self, bound: &[u32], weights: &[BigUint]) -> (BigUint, BigUint) {
+        let base = BigUint::from(self.base);
+        let mut count = BigUint::from(0_usize);
+        let mut sum = BigUint::from(0_usize);
+        let mut fixed = BigUint::from(0_usize);  // the sum of the digits so far, equal to the bound's
+
+        for (i, (&top, weight)) in bound.iter().zip(weights).enumerate() {
+            let free = weights.len() - 1 - i;
+            let ways = base.pow(free as u32);
+            // Each free digit is every digit equally often, so adds up to this over all the ways.
+            let free_digits = if free == 0 { BigUint::from(0_usize) } else { base.pow(free as u32 - 1) * (self.base * (self.base - 1) / 2) };
+            let free_sum: BigUint = weights[i + 1..].iter().map(|w| { w * &free_digits }).sum();
+
+            let first = if i == 0 { 1 } else { 0 };
+            for d in first..top {
+                count += &ways;
+                sum += (&fixed + weight * d) * &ways + &free_sum;
+            }
+            fixed += weight * top;
+        }
+
+        (count, sum)
+    }
+}
+
+impl Predicate for Palindromes {
+    fn matches(&self, id: &BigUint) -> bool {
+        let digits = digits_of(id, self.base);
+        digits.iter().eq(digits.iter().rev())
+    }
+
+    fn tally_to(&self, high: &BigUint) -> Result<Tally<BigUint>, SolveError> {
+        let mut tally = Tally::zero();
+        if *high == BigUint::from(0_usize) {
+            return Ok(tally);
+        }
+
+        let digits = digits_of(high, self.base);
+        for length in 1..=digits.len() {
+            let weights = self.weights(length);
+            let half = weights.len();
+            let bound = if length < digits.len() {
+                // One past the largest first half: base, then zeros.
+                let mut bound = vec![0; half];
+                bound[0] = self.base;
+                bound
+            }
+            else {
+                digits[..half].to_vec()
+            };
+
+            let (count, sum) = self.below(&bound, &weights);
+            tally.count += count;
+            tally.sum += sum;
+
+            if length == digits.len() {
+                // The palindrome with the same first half as `high` may or may not be past it.
+                let own: BigUint = bound.iter().zip(&weights).map(|(d, w)| { w * *d }).sum();
+                if own <= *high {
+                    tally.count += 1_usize;
+                    tally.sum += own;
+                }
+            }
+        }
+
+        Ok(tally)
+    }
+}
+
+impl std::fmt::Display for Palindromes {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "base {}, palindromes", self.base)
+    }
+}
+
+// Ids whose digits never go down, like 1229.
+pub struct NonDecreasing {
+    base: u32,
+}
+
+impl NonDecreasing {
+    pub fn new(base: u32) -> Result<NonDecreasing, SolveError> {
+        check_base(base)?;
+
+        Ok(NonDecreasing { base })
+    }
+}
+
+// The state is the last digit.
+impl Machine for NonDecreasing {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        0
+    }
+
+    fn step(&self, last: &u32, digit: u32) -> Option<u32> {
+        (digit >= *last).then_some(digit)
+    }
+
+    fn accepts(&self, _last: &u32) -> bool {
+        true
+    }
+}
+
+impl Predicate for NonDecreasing {
+    fn matches(&self, id: &BigUint) -> bool {
+        digits_of(id, self.base).is_sorted()
+    }
+
+    fn tally_to(&self, high: &BigUint) -> Result<Tally<BigUint>, SolveError> {
+        Ok(tally_machine(self, self.base, high))
+    }
+}
+
+impl std::fmt::Display for NonDecreasing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "base {}, non-decreasing digits", self.base)
+    }
+}
+
+// Ids whose digits (0-9 then a-z, in `base`) match a regular expression from start to end.
+pub struct Pattern {
+    base: u32,
+    source: String,
+    dfa: dense::DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl Pattern {
+    pub fn new(base: u32, pattern: &str) -> Result<Pattern, SolveError> {
+        check_base(base)?;
+        let bad_pattern = |e: String| { SolveError::Unsolvable(format!("Bad pattern '{pattern}': {e}")) };
+
+        let dfa = dense::DFA::new(&format!("^(?:{pattern})$")).map_err(|e| { bad_pattern(e.to_string()) })?;
+        let start = dfa.start_state(&start::Config::new().anchored(Anchored::Yes)).map_err(|e| { bad_pattern(e.to_string()) })?;
+
+        Ok(Pattern { base, source: pattern.to_string(), dfa, start })
+    }
+}
+
+// The state is the DFA's.
+impl Machine for Pattern {
+    type State = StateID;
+
+    fn start(&self) -> StateID {
+        self.start
+    }
+
+    fn step(&self, state: &StateID, digit: u32) -> Option<StateID> {
+        let c = char::from_digit(digit, self.base)?;
+        let next = self.dfa.next_state(*state, c as u8);
+        (!self.dfa.is_dead_state(next)).then_some(next)
+    }
+
+    fn accepts(&self, state: &StateID) -> bool {
+        self.dfa.is_match_state(self.dfa.next_eoi_state(*state))
+    }
+}
+
+impl Predicate for Pattern {
+    fn matches(&self, id: &BigUint) -> bool {
+        digits_of(id, self.base).into_iter()
+            .try_fold(self.start, |state, d| { self.step(&state, d) })
+            .is_some_and(|state| { self.accepts(&state) })
+    }
+
+    fn tally_to(&self, high: &BigUint) -> Result<Tally<BigUint>, SolveError> {
+        Ok(tally_machine(self, self.base, high))
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "base {}, matching '{}'", self.base, self.source)
+    }
 }
 
 impl Range {
-    const MAX_DIGITS: u32 = 10;
-
-    fn invalids(&self) -> HashSet<usize> {
-        let mut invalids = HashSet::new();
-        // println!("Looking for invalid values in range {} - {}", self.low, self.high);
-
-        for exp in 1..6 {
-            let base = 10_usize.pow(exp);          // 10, 100, ... 1000000
-            let min_kernel = base/10;              //  1,  10, ...  100000
-            let max_kernel = base-1;               //  9,  99, ...  999999
-            let multiplier = base + 1;             // 11, 101, ... 1000001
-            let lowest = min_kernel * multiplier;  // 11, 1010, ... 100000100000
-            let highest = max_kernel * multiplier; // 99, 9999, ... 999999999999
-
-            if (lowest <= self.high) && (highest >= self.low) {
-                // Look for invalid numbers with this base.
-                let bottom_kernel = max(min_kernel, (self.low as f64 / multiplier as f64).ceil() as usize);
-                let top_kernel = min(max_kernel, (self.high as f64 / multiplier as f64).floor() as usize);
-
-                for k in bottom_kernel ..= top_kernel {
-                    // println!("    found {}", k * multiplier);
-                    invalids.insert(k * multiplier);
+    // The most ids list() will list.
+    const MAX_LISTED: usize = 10_000;
+
+    #[cfg(test)]
+    fn new(low: usize, high: usize) -> Range {
+        Range { low: BigUint::from(low), high: BigUint::from(high) }
+    }
+
+    // Parse a range like "11-22".  Lines are numbered from 1.
+    fn parse(line_no: usize, part: &str) -> Result<Range, SolveError> {
+        let bad_range = || { SolveError::parse(line_no, format!("Bad range '{part}'")) };
+
+        let components: Vec<BigUint> = part.trim().split("-")
+            .map(|s| {
+                if s.is_empty() || !s.chars().all(|c| { c.is_ascii_digit() }) {
+                    return Err(bad_range());
+                }
+                s.parse::<BigUint>().map_err(|_| { bad_range() })
+             })
+             .collect::<Result<_, _>>()?;
+        let [low, high] = <[BigUint; 2]>::try_from(components).map_err(|_| { bad_range() })?;
+
+        Ok(Range { low, high })
+    }
+
+    // Count and sum the invalid ids without listing them.  The ids of D digits whose L digit
+    // kernel repeats m times are k * M for a span of kernels k, where M is 1 + 10^L + ... +
+    // 10^((m-1)L), so their sum is M times an arithmetic series.  Ids that repeat more than one
+    // way are sorted out by Rule::coefficients().
+    fn tally<T: Arith>(&self, rule: &Rule) -> Result<Tally<T>, SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "sum of invalid ids") };
+        let one = T::from_usize(1);
+        let two = T::from_usize(2);
+
+        // Added and subtracted terms are kept apart, as T can't go negative.
+        let (mut count, mut sum) = (T::from_usize(0), T::from_usize(0));
+        let (mut less_count, mut less_sum) = (T::from_usize(0), T::from_usize(0));
+
+        let (low_digits, high_digits) = (rule.digits(&self.low), rule.digits(&self.high));
+        for digits in max(low_digits, 2)..=high_digits {
+            // The part of the range with this many digits.
+            let a = if digits == low_digits {
+                T::from_big(&self.low).ok_or_else(overflow)?
+            }
+            else {
+                rule.pow(digits - 1).ok_or_else(overflow)?
+            };
+            let b = if digits == high_digits {
+                T::from_big(&self.high).ok_or_else(overflow)?
+            }
+            else {
+                rule.pow::<T>(digits).and_then(|p| { p.checked_sub(&one) }).ok_or_else(overflow)?
+            };
+            if a > b {
+                continue;
+            }
+
+            for (m, c) in rule.coefficients(digits) {
+                // A multiplier too big for T is too big for any kernel in the range.
+                let shift: T = rule.pow(digits / m).ok_or_else(overflow)?;
+                let Some(multiplier) = (1..m).try_fold(one.clone(), |mul, _| {
+                    mul.checked_mul(&shift)?.checked_add(&one)
+                })
+                else {
+                    continue;
+                };
+
+                let first = a.checked_sub(&one).and_then(|x| { x.checked_div(&multiplier) }).ok_or_else(overflow)? + one.clone();
+                let last = b.checked_div(&multiplier).ok_or_else(overflow)?;
+                if first > last {
+                    continue;
                 }
+                let n = last.clone() - first.clone() + one.clone();
+
+                // n * (first + last) / 2, halving whichever is even so nothing overflows early.
+                let ends = first.checked_add(&last).ok_or_else(overflow)?;
+                let series = if n.clone() / two.clone() * two.clone() == n {
+                    (n.clone() / two.clone()).checked_mul(&ends)
+                }
+                else {
+                    n.checked_mul(&(ends / two.clone()))
+                };
+                let series = series.and_then(|s| { s.checked_mul(&multiplier) }).ok_or_else(overflow)?;
+
+                let times = T::from_usize(c.unsigned_abs() as usize);
+                let n = n.checked_mul(&times).ok_or_else(overflow)?;
+                let series = series.checked_mul(&times).ok_or_else(overflow)?;
+                if c > 0 {
+                    count = count.checked_add(&n).ok_or_else(overflow)?;
+                    sum = sum.checked_add(&series).ok_or_else(overflow)?;
+                }
+                else {
+                    less_count = less_count.checked_add(&n).ok_or_else(overflow)?;
+                    less_sum = less_sum.checked_add(&series).ok_or_else(overflow)?;
+                }
+            }
+        }
+
+        Ok(Tally { count: count - less_count, sum: sum - less_sum })
+    }
+
+    // Count and sum the ids matching any predicate.
+    fn tally_with(&self, predicate: &dyn Predicate) -> Result<Tally<BigUint>, SolveError> {
+        if self.low > self.high {
+            return Ok(Tally::zero());
+        }
+        let upto_high = predicate.tally_to(&self.high)?;
+        let upto_low = if self.low == BigUint::from(0_usize) {
+            Tally::zero()
+        }
+        else {
+            predicate.tally_to(&(&self.low - 1_usize))?
+        };
+
+        Ok(Tally { count: upto_high.count - upto_low.count, sum: upto_high.sum - upto_low.sum })
+    }
+
+    // Count and sum the invalid ids by listing them.  Past MAX_LISTED, it gives up.
+    fn tally_listed<T: Arith>(&self, rule: &Rule) -> Result<Tally<T>, SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "sum of invalid ids") };
+
+        let (mut count, mut sum) = (0, T::from_usize(0));
+        for id in self.invalids(rule) {
+            count += 1;
+            if count > Range::MAX_LISTED {
+                return Err(SolveError::Unsolvable(format!("Too many invalid ids to list in {}-{}", self.low, self.high)));
+            }
+            sum = T::from_big(&id).and_then(|id| { sum.checked_add(&id) }).ok_or_else(overflow)?;
+        }
+
+        Ok(Tally { count: T::from_usize(count), sum })
+    }
+
+    // The invalid ids, smallest first, worked out as they're needed.
+    fn invalids(&self, rule: &Rule) -> Invalids {
+        trace::note(|| { format!("Looking for invalid values ({rule}) in range {} - {}", self.low, self.high) });
+
+        Invalids { rule: *rule, next: self.low.clone(), high: self.high.clone(), end: BigUint::from(0_usize), queue: BinaryHeap::new() }
+    }
+}
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}-{}", self.low, self.high)
+    }
+}
+
+// What to do with ranges that overlap or run backwards, like 22-11.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Overlaps {
+    // Take the ranges as they are: ids in two ranges count twice, and backwards ranges have none.
+    AsGiven,
+
+    // Turn backwards ranges around and merge ranges that overlap or touch.
+    Merge,
+
+    // Report them as errors in the input.
+    Reject,
+}
+
+impl Overlaps {
+    pub fn parse(s: &str) -> Option<Overlaps> {
+        match s {
+            "given" => Some(Overlaps::AsGiven),
+            "merge" => Some(Overlaps::Merge),
+            "reject" => Some(Overlaps::Reject),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Overlaps {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Overlaps::AsGiven => write!(f, "overlaps as given"),
+            Overlaps::Merge => write!(f, "overlaps merged"),
+            Overlaps::Reject => write!(f, "overlaps rejected"),
+        }
+    }
+}
+
+// A range's share of the ids, for a breakdown.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RangeTally {
+    pub line: usize,
+    pub low: BigUint,
+    pub high: BigUint,
+    pub tally: Tally<BigUint>,
+}
+
+// The invalid ids in a range, in order.  Ids with the same number of digits are merged from one
+// sequence per allowed repeat count: the kernels k (of the right number of digits) times that
+// count's multiplier.  An id in more than one sequence is only given once, and when the rule is
+// exact, ids whose shortest kernel repeats another number of times are passed over.
+//
+// nth() and count() work it out from Range::tally() instead of going through the ids.
+struct Invalids {
+    rule: Rule,
+    next: BigUint,  // every id from here to `high` is still to come
+    high: BigUint,
+    end: BigUint,   // the last id with as many digits as those queued
+    queue: BinaryHeap<Reverse<(BigUint, BigUint)>>,  // the next id of each sequence, and its multiplier
+}
+
+impl Invalids {
+    // Queue up the sequences for ids with as many digits as `next`.
+    fn start_digits(&mut self) {
+        let digits = self.rule.digits(&self.next);
+        let base = BigUint::from(self.rule.base);
+        let one = BigUint::from(1_usize);
+        self.end = min(base.pow(digits as u32) - &one, self.high.clone());
+
+        for repeats in self.rule.repeats(digits) {
+            let shift = base.pow((digits / repeats) as u32);                  // 10, 100, ... in base 10
+            let multiplier = (1..repeats).fold(one.clone(), |m, _| { m * &shift + &one });  // 111, 10101, ... (for 3 repeats)
+            let kernel = max(&shift / &base, (&self.next + &multiplier - &one) / &multiplier);
+            let id = kernel * &multiplier;
+            if id <= self.end {
+                self.queue.push(Reverse((id, multiplier)));
             }
         }
+    }
 
-        invalids
+    // How many ids are still to come.
+    fn remaining(&self) -> BigUint {
+        self.count_to(&self.high)
     }
 
-    fn invalids2(&self) -> HashSet<usize> {
-        let mut invalids = HashSet::new();
-        // println!("Looking for invalid values (part 2) in range {} - {}", self.low, self.high);
+    // How many ids are still to come, up to `last`.
+    fn count_to(&self, last: &BigUint) -> BigUint {
+        if &self.next > last {
+            return BigUint::from(0_usize);
+        }
+        let range = Range { low: self.next.clone(), high: last.clone() };
+        // Tallies in big integers can't overflow.
+        range.tally::<BigUint>(&self.rule).map_or(BigUint::from(0_usize), |tally| { tally.count })
+    }
+}
+
+impl Iterator for Invalids {
+    type Item = BigUint;
 
-        for repeats in 2..=Self::MAX_DIGITS {
-            for exp in 1..Self::MAX_DIGITS/repeats+1 {
-                let base = 10_usize.pow(exp);          // 10, 100, ... 1000000
-                let min_kernel = base/10;              //  1,  10, ...  100000
-                let max_kernel = base-1;               //  9,  99, ...  999999
-                let mut multiplier = 1;                // 111, 10101, ... 1000001000001 (e.g. for repeats = 3)
-                for _ in 1..repeats {
-                    multiplier *= base;
-                    multiplier += 1;
+    fn next(&mut self) -> Option<BigUint> {
+        loop {
+            let Some(Reverse((id, multiplier))) = self.queue.pop() else {
+                if self.next > self.high {
+                    return None;
                 }
-                // println!("Checking with multiplier {multiplier}");
-                let lowest = min_kernel * multiplier;  // 11, 1010, ... 100000100000
-                let highest = max_kernel * multiplier; // 99, 9999, ... 999999999999
-
-                if (lowest <= self.high) && (highest >= self.low) {
-                    // Look for invalid numbers with this base.
-                    let bottom_kernel = max(min_kernel, (self.low as f64 / multiplier as f64).ceil() as usize);
-                    let top_kernel = min(max_kernel, (self.high as f64 / multiplier as f64).floor() as usize);
-
-                    for k in bottom_kernel ..= top_kernel {
-                        // println!("    found {}", k * multiplier);
-                        invalids.insert(k * multiplier);
-                    }
+                self.start_digits();
+                if self.queue.is_empty() {
+                    self.next = &self.end + 1_usize;
                 }
+                continue;
+            };
+
+            let after = &id + &multiplier;
+            if after <= self.end {
+                self.queue.push(Reverse((after, multiplier)));
+            }
+            if id < self.next {
+                // Already given, from another sequence.
+                continue;
             }
 
+            self.next = if self.queue.is_empty() { &self.end + 1_usize } else { &id + 1_usize };
+            if !self.rule.exact || self.rule.is_invalid(&id) {
+                return Some(id);
+            }
+        }
+    }
+
+    // Skips whole digit counts at a time, then finds the id by bisection.
+    fn nth(&mut self, n: usize) -> Option<BigUint> {
+        let mut n = BigUint::from(n);
+        self.queue.clear();
+
+        while self.next <= self.high {
+            let digits = self.rule.digits(&self.next);
+            let end = min(BigUint::from(self.rule.base).pow(digits as u32) - 1_usize, self.high.clone());
+            let count = self.count_to(&end);
+            if count <= n {
+                n -= count;
+                self.next = end + 1_usize;
+                continue;
+            }
+
+            // The first id with n + 1 ids from `next` up to it.
+            let (mut low, mut high) = (self.next.clone(), end);
+            while low < high {
+                let mid: BigUint = (&low + &high) / 2_usize;
+                if self.count_to(&mid) > n {
+                    high = mid;
+                }
+                else {
+                    low = mid + 1_usize;
+                }
+            }
+            self.next = &low + 1_usize;
+            return Some(low);
         }
 
-        invalids
+        None
+    }
+
+    // Panics past usize::MAX, as Iterator::count() may.
+    fn count(self) -> usize {
+        usize::try_from(self.remaining()).expect("Too many invalid ids to count in a usize")
     }
 }
 
 // A representation of the puzzle inputs.
 struct Input {
     ranges: Vec<Range>,
+    lines: Vec<usize>,  // the line each range is on
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut ranges = Vec::new();
+        let mut lines = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
             for part in line.split(",") {
-                let components: Vec<usize> = part.split("-")
-                    .map(|s| {
-                        s.parse::<usize>().unwrap()
-                     })
-                     .collect();
-                assert_eq!(components.len(), 2);
-                let low = components[0];
-                let high = components[1];
+                ranges.push(Range::parse(line_no + 1, part)?);
+                lines.push(line_no + 1);
+            }
+        }
 
-                ranges.push(Range { low, high })
+        Ok(Input { ranges, lines })
+    }
+
+    // Backwards and overlapping ranges, in line order.  An overlap is reported on the line of
+    // the later of the two ranges.
+    fn problems(&self) -> Vec<SolveError> {
+        let mut problems = Vec::new();
+
+        let mut forwards = Vec::new();
+        for (i, range) in self.ranges.iter().enumerate() {
+            if range.low > range.high {
+                problems.push(SolveError::parse(self.lines[i], format!("Range {range} runs backwards.")));
+            }
+            else {
+                forwards.push(i);
             }
         }
 
-        Input { ranges }
+        // Going up by low end, each range against the one reaching furthest before it.
+        forwards.sort_by(|a, b| { self.ranges[*a].low.cmp(&self.ranges[*b].low) });
+        let mut furthest: Option<usize> = None;
+        for i in forwards {
+            match furthest {
+                Some(f) if self.ranges[i].low <= self.ranges[f].high => {
+                    let (earlier, later) = (min(f, i), max(f, i));
+                    problems.push(SolveError::parse(self.lines[later],
+                        format!("Range {} overlaps {} on line {}.", self.ranges[later], self.ranges[earlier], self.lines[earlier])));
+                    if self.ranges[i].high > self.ranges[f].high {
+                        furthest = Some(i);
+                    }
+                }
+                _ => furthest = Some(i),
+            }
+        }
+        problems.sort_by_key(|p| { p.line() });
+
+        problems
+    }
+
+    // Turn backwards ranges around, then merge ranges that overlap or touch, going up by low
+    // end.  A merged range is on the first line of those in it.
+    fn merge(&mut self) {
+        let mut ranges: Vec<(Range, usize)> = self.ranges.drain(..).zip(self.lines.drain(..))
+            .map(|(range, line_no)| {
+                if range.low > range.high { (Range { low: range.high, high: range.low }, line_no) } else { (range, line_no) }
+            })
+            .collect();
+        ranges.sort_by(|(a, _), (b, _)| { a.low.cmp(&b.low) });
+
+        for (range, line_no) in ranges {
+            match (self.ranges.last_mut(), self.lines.last_mut()) {
+                (Some(last), Some(last_line)) if range.low <= &last.high + 1_usize => {
+                    last.high = max(last.high.clone(), range.high);
+                    *last_line = min(*last_line, line_no);
+                }
+                _ => {
+                    self.ranges.push(range);
+                    self.lines.push(line_no);
+                }
+            }
+        }
+    }
+
+    // Deal with overlapping and backwards ranges.
+    fn normalize(&mut self, overlaps: Overlaps) -> Result<(), SolveError> {
+        match overlaps {
+            Overlaps::AsGiven => Ok(()),
+            Overlaps::Merge => {
+                self.merge();
+                Ok(())
+            }
+            Overlaps::Reject => match self.problems().into_iter().next() {
+                Some(problem) => Err(problem),
+                None => Ok(()),
+            },
+        }
+    }
+
+    // Random input in the format read() accepts: up to `size` ranges on one line.
+    // Ranges don't overlap and stay within GENERATED_DIGITS digits.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const GENERATED_DIGITS: u32 = 10;
+
+        let mut ranges = Vec::new();
+        for _ in 0..size {
+            let digits = rng.range(1, GENERATED_DIGITS as usize) as u32;
+            let low = rng.range(10_usize.pow(digits-1), 10_usize.pow(digits) - 1);
+            let high = low + rng.range(0, min(low, 100_000));
+            ranges.push((low, min(high, 10_usize.pow(GENERATED_DIGITS) - 1)));
+        }
+
+        // Trim overlaps, dropping ranges that disappear entirely.
+        ranges.sort();
+        let mut disjoint: Vec<(usize, usize)> = Vec::new();
+        for (low, high) in ranges {
+            let low = match disjoint.last() {
+                Some((_, prev_high)) => max(low, prev_high + 1),
+                None => low,
+            };
+            if low <= high {
+                disjoint.push((low, high));
+            }
+        }
+        rng.shuffle(&mut disjoint);
+
+        let parts: Vec<String> = disjoint.iter()
+            .map(|(low, high)| { format!("{low}-{high}") })
+            .collect();
+
+        parts.join(",") + "\n"
     }
 }
 
+// The rules for parts 1 and 2, and what to do with overlapping ranges.
 pub struct Day2 {
+    rules: [Rule; 2],
+    overlaps: Overlaps,
+}
+
+// How to add up a range's invalid ids: with Range::tally() or Range::tally_listed().
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Method {
+    Formula,
+    Listed,
 }
 
 // Day2
 impl Day2 {
     pub const fn new() -> Self {
-        Self { }
+        Self::with_rules(Rule::PART1, Rule::PART2)
+    }
+
+    // Solve with other rules for which ids are invalid.
+    pub const fn with_rules(part1: Rule, part2: Rule) -> Self {
+        Self { rules: [part1, part2], overlaps: Overlaps::AsGiven }
+    }
+
+    // Solve with overlapping and backwards ranges dealt with another way.
+    pub const fn with_overlaps(self, overlaps: Overlaps) -> Self {
+        Self { overlaps, ..self }
+    }
+
+    // The ranges, dealt with as `overlaps` says.
+    fn input(&self, text: &str) -> Result<Input, SolveError> {
+        let mut input = Input::read(text)?;
+        input.normalize(self.overlaps)?;
+
+        Ok(input)
+    }
+
+    // Add the invalid ids in a range to a tally.
+    fn add_invalids<T: Arith>(tally: &mut Tally<T>, range: &Range, rule: &Rule, method: Method) -> Result<(), SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "sum of invalid ids") };
+        let more = match method {
+            Method::Formula => range.tally::<T>(rule)?,
+            Method::Listed => range.tally_listed::<T>(rule)?,
+        };
+        tally.count = tally.count.checked_add(&more.count).ok_or_else(overflow)?;
+        tally.sum = tally.sum.checked_add(&more.sum).ok_or_else(overflow)?;
+
+        Ok(())
+    }
+
+    fn tally_invalids<T: Arith>(ranges: &[Range], rule: &Rule, method: Method) -> Result<Tally<T>, SolveError> {
+        let mut tally = Tally::zero();
+        for range in ranges {
+            Day2::add_invalids(&mut tally, range, rule, method)?;
+        }
+
+        Ok(tally)
+    }
+
+    fn stream_invalids<T: Arith>(reader: &mut dyn BufRead, rule: &Rule) -> Result<Answer, SolveError> {
+        let mut tally: Tally<T> = Tally::zero();
+        for_each_record(reader, b',', |line_no, record| {
+            // A record can span lines when ranges are on separate lines.
+            for (n, part) in record.lines().enumerate() {
+                Day2::add_invalids(&mut tally, &Range::parse(line_no + n, part)?, rule, Method::Formula)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(tally.sum.into_answer())
+    }
+
+    // The rule for a part, if there is one.
+    fn rule(&self, part: usize) -> Option<&Rule> {
+        self.rules.get(part.checked_sub(1)?)
+    }
+
+    fn solve(&self, text: &str, part: usize, method: Method) -> Result<Answer, SolveError> {
+        let (_, sum) = self.count_and_sum_with(text, part, method)?;
+        Ok(sum)
+    }
+
+    // The number of invalid ids under a part's rule, and their sum.
+    pub fn count_and_sum(&self, text: &str, part: usize) -> Result<(Answer, Answer), SolveError> {
+        self.count_and_sum_with(text, part, Method::Formula)
+    }
+
+    // The number of ids matching a predicate, and their sum.
+    pub fn count_and_sum_matching(&self, text: &str, predicate: &dyn Predicate) -> Result<(Answer, Answer), SolveError> {
+        let mut tally: Tally<BigUint> = Tally::zero();
+        for range in self.breakdown(text, predicate)? {
+            tally.count += range.tally.count;
+            tally.sum += range.tally.sum;
+        }
+
+        Ok(tally.into_answers())
+    }
+
+    // The number of ids matching a predicate, and their sum, range by range.
+    pub fn breakdown(&self, text: &str, predicate: &dyn Predicate) -> Result<Vec<RangeTally>, SolveError> {
+        let input = self.input(text)?;
+
+        input.ranges.iter().zip(&input.lines)
+            .map(|(range, line_no)| {
+                let tally = range.tally_with(predicate)?;
+                Ok(RangeTally { line: *line_no, low: range.low.clone(), high: range.high.clone(), tally })
+            })
+            .collect()
+    }
+
+    fn count_and_sum_with(&self, text: &str, part: usize, method: Method) -> Result<(Answer, Answer), SolveError> {
+        let rule = self.rule(part).ok_or_else(|| { SolveError::Unsolvable(format!("No part {part}.")) })?;
+
+        // Read input file into Input struct
+        let input = self.input(text)?;
+
+        arith::widen(|| { Day2::tally_invalids::<usize>(&input.ranges, rule, method).map(Tally::into_answers) },
+                     || { Day2::tally_invalids::<u128>(&input.ranges, rule, method).map(Tally::into_answers) },
+                     || { Day2::tally_invalids::<BigUint>(&input.ranges, rule, method).map(Tally::into_answers) })
     }
 }
 
 impl Day for Day2 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        // Read input file into Input struct
-        let input = Input::read(text);
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
+        self.solve(text, 1, Method::Formula)
+    }
 
-        let sum = input.ranges.iter()
-            .map(|range| {
-                range.invalids().iter()
-                    .sum::<usize>()
-            }).sum();
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
+        self.solve(text, 2, Method::Formula)
+    }
+
+    fn strategies(&self, _part: usize) -> Vec<&'static str> {
+        vec![DEFAULT_STRATEGY, "listed"]
+    }
 
-        Answer::Numeric(sum)
+    fn solve_with(&self, part: usize, strategy: &str, text: &str) -> Result<Answer, SolveError> {
+        match (part, strategy) {
+            (1, DEFAULT_STRATEGY) => self.try_part1(text),
+            (2, DEFAULT_STRATEGY) => self.try_part2(text),
+            (1 | 2, "listed") => self.solve(text, part, Method::Listed),
+            _ => Err(SolveError::NoStrategy { part, strategy: strategy.to_string() }),
+        }
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    // The ranges are usually all on one line, so this reads a range at a time rather than a line.
+    // Ranges are taken as given: finding overlaps needs all of them.
+    fn solve_stream(&self, part: usize, reader: &mut dyn BufRead) -> Option<Result<Answer, SolveError>> {
+        if self.overlaps != Overlaps::AsGiven {
+            return None;
+        }
+        let rule = self.rule(part)?;
 
-        // Read input file into Input struct
-        let input = Input::read(text);
+        Some(arith::widest(reader,
+                           |r| { Day2::stream_invalids::<usize>(r, rule) },
+                           |r| { Day2::stream_invalids::<u128>(r, rule) },
+                           |r| { Day2::stream_invalids::<BigUint>(r, rule) }))
+    }
 
-        let sum = input.ranges.iter()
-            .map(|range| {
-                range.invalids2().iter()
-                    .sum::<usize>()
-            }).sum();
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        match Input::read(text) {
+            Ok(input) => Some(input.problems()),
+            Err(e) => Some(vec![e]),
+        }
+    }
 
-        Answer::Numeric(sum)
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // Each range's invalid ids by both parts' rules, then the totals.
+    fn inspect(&self, text: &str) -> Option<Result<String, SolveError>> {
+        let breakdowns = self.breakdown(text, &self.rules[0]).and_then(|part1| {
+            Ok((part1, self.breakdown(text, &self.rules[1])?))
+        });
+        let (part1, part2) = match breakdowns {
+            Ok(breakdowns) => breakdowns,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut text = format!("{} ranges, {}\n", part1.len(), self.overlaps);
+        let mut totals: [Tally<BigUint>; 2] = [Tally::zero(), Tally::zero()];
+        for (r1, r2) in part1.iter().zip(&part2) {
+            text.push_str(&format!("  line {}: {}-{}: part 1 {} ids, sum {}; part 2 {} ids, sum {}\n",
+                                   r1.line, r1.low, r1.high, r1.tally.count, r1.tally.sum, r2.tally.count, r2.tally.sum));
+            for (total, r) in totals.iter_mut().zip([r1, r2]) {
+                total.count += &r.tally.count;
+                total.sum += &r.tally.sum;
+            }
+        }
+        text.push_str(&format!("total: part 1 {} ids, sum {}; part 2 {} ids, sum {}\n",
+                               totals[0].count, totals[0].sum, totals[1].count, totals[1].sum));
+
+        Some(Ok(text))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day2::{Day2, Input, Range};
-    use crate::day::{Day, Answer};
+    use crate::day2::{Day2, Input, NonDecreasing, Overlaps, Palindromes, Pattern, Predicate, Range, Rule, Tally};
+    use std::cmp::{max, min};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::arith::{set_policy, Policy};
+    use std::collections::HashSet;
+    use num_bigint::BigUint;
+
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124
 ";
 
+    fn big(n: usize) -> BigUint {
+        BigUint::from(n)
+    }
+
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.ranges.len(), 11);
-        assert_eq!(input.ranges[0].low, 11);
-        assert_eq!(input.ranges[0].high, 22);
+        assert_eq!(input.ranges[0].low, big(11));
+        assert_eq!(input.ranges[0].high, big(22));
     }
 
     #[test]
     fn test_invalid() {
-        let range = Range{low: 11, high:22};
-        let invalid_set = range.invalids();
+        let range = Range::new(11, 22);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 2);
-        assert!(invalid_set.contains(&11));
-        assert!(invalid_set.contains(&22));
+        assert!(invalid_set.contains(&big(11)));
+        assert!(invalid_set.contains(&big(22)));
 
-        let range = Range{low: 98, high:115};
-        let invalid_set = range.invalids();
+        let range = Range::new(98, 115);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&99));
+        assert!(invalid_set.contains(&big(99)));
 
-        let range = Range{low: 998, high:1012};
-        let invalid_set = range.invalids();
+        let range = Range::new(998, 1012);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&1010));
+        assert!(invalid_set.contains(&big(1010)));
 
-        let range = Range{low: 1188511880, high:1188511890};
-        let invalid_set = range.invalids();
+        let range = Range::new(1188511880, 1188511890);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&1188511885));
+        assert!(invalid_set.contains(&big(1188511885)));
 
-        let range = Range{low: 222220, high:222224};
-        let invalid_set = range.invalids();
+        let range = Range::new(222220, 222224);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&222222));
+        assert!(invalid_set.contains(&big(222222)));
 
-        let range = Range{low: 1698522, high:1698528};
-        let invalid_set = range.invalids();
+        let range = Range::new(1698522, 1698528);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 0);
 
-        let range = Range{low: 446443, high:446449};
-        let invalid_set = range.invalids();
+        let range = Range::new(446443, 446449);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&446446));
+        assert!(invalid_set.contains(&big(446446)));
 
-        let range = Range{low: 38593856, high:38593862};
-        let invalid_set = range.invalids();
+        let range = Range::new(38593856, 38593862);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&38593859));
+        assert!(invalid_set.contains(&big(38593859)));
 
-        let range = Range{low: 565653, high:565659};
-        let invalid_set = range.invalids();
+        let range = Range::new(565653, 565659);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 0);
         
-        let range = Range{low: 824824821, high:824824827};
-        let invalid_set = range.invalids();
+        let range = Range::new(824824821, 824824827);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 0);
 
-        let range = Range{low: 2121212118, high:2121212124};
-        let invalid_set = range.invalids();
+        let range = Range::new(2121212118, 2121212124);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART1).collect();
         assert_eq!(invalid_set.len(), 0);
     }
 
     
     #[test]
     fn test_invalid2() {
-        let range = Range{low: 11, high:22};
-        let invalid_set = range.invalids2();
+        let range = Range::new(11, 22);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 2);
-        assert!(invalid_set.contains(&11));
-        assert!(invalid_set.contains(&22));
+        assert!(invalid_set.contains(&big(11)));
+        assert!(invalid_set.contains(&big(22)));
 
-        let range = Range{low: 98, high:115};
-        let invalid_set = range.invalids2();
+        let range = Range::new(98, 115);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 2);
-        assert!(invalid_set.contains(&99));
-        assert!(invalid_set.contains(&111));
+        assert!(invalid_set.contains(&big(99)));
+        assert!(invalid_set.contains(&big(111)));
 
-        let range = Range{low: 998, high:1012};
-        let invalid_set = range.invalids2();
+        let range = Range::new(998, 1012);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 2);
-        assert!(invalid_set.contains(&1010));
-        assert!(invalid_set.contains(&999));
+        assert!(invalid_set.contains(&big(1010)));
+        assert!(invalid_set.contains(&big(999)));
 
-        let range = Range{low: 1188511880, high:1188511890};
-        let invalid_set = range.invalids2();
+        let range = Range::new(1188511880, 1188511890);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&1188511885));
+        assert!(invalid_set.contains(&big(1188511885)));
 
-        let range = Range{low: 222220, high:222224};
-        let invalid_set = range.invalids2();
+        let range = Range::new(222220, 222224);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&222222));
+        assert!(invalid_set.contains(&big(222222)));
 
-        let range = Range{low: 1698522, high:1698528};
-        let invalid_set = range.invalids2();
+        let range = Range::new(1698522, 1698528);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 0);
 
-        let range = Range{low: 446443, high:446449};
-        let invalid_set = range.invalids2();
+        let range = Range::new(446443, 446449);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&446446));
+        assert!(invalid_set.contains(&big(446446)));
 
-        let range = Range{low: 38593856, high:38593862};
-        let invalid_set = range.invalids2();
+        let range = Range::new(38593856, 38593862);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&38593859));
+        assert!(invalid_set.contains(&big(38593859)));
 
-        let range = Range{low: 565653, high:565659};
-        let invalid_set = range.invalids2();
+        let range = Range::new(565653, 565659);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&565656));
+        assert!(invalid_set.contains(&big(565656)));
         
-        let range = Range{low: 824824821, high:824824827};
-        let invalid_set = range.invalids2();
+        let range = Range::new(824824821, 824824827);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&824824824));
+        assert!(invalid_set.contains(&big(824824824)));
 
-        let range = Range{low: 2121212118, high:2121212124};
-        let invalid_set = range.invalids2();
+        let range = Range::new(2121212118, 2121212124);
+        let invalid_set: HashSet<BigUint> = range.invalids(&Rule::PART2).collect();
         assert_eq!(invalid_set.len(), 1);
-        assert!(invalid_set.contains(&2121212121));
+        assert!(invalid_set.contains(&big(2121212121)));
     }
 
     #[test]
@@ -294,5 +1288,341 @@ mod test {
         let d: Day2 = Day2::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(4174379265));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Every part 1 invalid id is also a part 2 invalid id.
+    fn test_generate() {
+        let d = Day2::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert!(!input.ranges.is_empty());
+            assert!(input.ranges.iter().all(|r| { r.low <= r.high }));
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p1 <= p2);
+        }
+    }
+
+    // Is the id some digit string repeated at least `min_repeats` times (or exactly
+    // `min_repeats` times when `exact`)?
+    fn is_repeat(id: usize, min_repeats: usize, exact: bool) -> bool {
+        let digits = id.to_string();
+        (min_repeats..=digits.len())
+            .filter(|repeats| { !exact || *repeats == min_repeats })
+            .filter(|repeats| { digits.len().is_multiple_of(*repeats) })
+            .any(|repeats| { digits[..digits.len() / repeats].repeat(repeats) == digits })
+    }
+
+    #[test]
+    // Compare invalids() against checking every id's digit string.
+    fn test_oracle() {
+        let mut rng = Rng::new(2);
+        for _ in 0..300 {
+            let digits = rng.range(1, 7) as u32;
+            let low = rng.range(1, 10_usize.pow(digits));
+            let high = low + rng.range(0, 2000);
+            let range = Range::new(low, high);
+
+            let expected: Vec<BigUint> = (low..=high).filter(|id| { is_repeat(*id, 2, true) }).map(big).collect();
+            assert_eq!(range.invalids(&Rule::PART1).collect::<Vec<_>>(), expected, "range {low}-{high}");
+
+            let expected: Vec<BigUint> = (low..=high).filter(|id| { is_repeat(*id, 2, false) }).map(big).collect();
+            assert_eq!(range.invalids(&Rule::PART2).collect::<Vec<_>>(), expected, "range {low}-{high}");
+        }
+    }
+
+    #[test]
+    // Skipping ahead with nth() and counting give the same ids as going through them one by one.
+    fn test_invalids_iter() {
+        let rules = [Rule::PART1, Rule::PART2, Rule::new(10, 2, 4, true).unwrap(), Rule::new(2, 3, usize::MAX, false).unwrap()];
+        let mut rng = Rng::new(48);
+        for _ in 0..100 {
+            let low = rng.range(1, 10_000_000);
+            let high = low + rng.range(0, 100_000);
+            let range = Range::new(low, high);
+            for rule in &rules {
+                let ids: Vec<BigUint> = range.invalids(rule).collect();
+                assert_eq!(range.invalids(rule).count(), ids.len(), "range {low}-{high}, {rule}");
+                for n in [0, 1, ids.len() / 2, ids.len().saturating_sub(1), ids.len(), ids.len() + 1] {
+                    assert_eq!(range.invalids(rule).nth(n).as_ref(), ids.get(n), "range {low}-{high}, {rule}, n {n}");
+                }
+
+                let mut it = range.invalids(rule);
+                let mixed = [it.next(), it.nth(2), it.next()];
+                assert_eq!(mixed, [ids.first(), ids.get(3), ids.get(4)].map(|id| { id.cloned() }), "range {low}-{high}, {rule}");
+            }
+        }
+
+        // Far too many to list, but the first few, the millionth and how many there are come quickly.
+        let range = Range::parse(1, &format!("1-{}", "9".repeat(40))).unwrap();
+        let first: Vec<BigUint> = range.invalids(&Rule::PART1).take(4).collect();
+        assert_eq!(first, vec![big(11), big(22), big(33), big(44)]);
+        assert_eq!(range.invalids(&Rule::PART1).nth(999_999), Some(big(10_000_001_000_000)));
+        assert_eq!(range.invalids(&Rule::PART1).remaining(), BigUint::from(10_usize).pow(20) - 1_usize);
+        assert_eq!(range.invalids(&Rule::PART2).take_while(|id| { id < &big(1000) }).count(), 9 + 9);
+    }
+
+    #[test]
+    // The formula matches listing the ids, including ids that repeat more than one way.
+    fn test_tally() {
+        let mut rng = Rng::new(45);
+        for _ in 0..300 {
+            let digits = rng.range(1, 8) as u32;
+            let low = rng.range(1, 10_usize.pow(digits));
+            let high = low + rng.range(0, 20_000);
+            let range = Range::new(low, high);
+            for rule in [Rule::PART1, Rule::PART2] {
+                assert_eq!(range.tally::<usize>(&rule).unwrap(), range.tally_listed::<usize>(&rule).unwrap(),
+                           "range {low}-{high}, {rule}");
+            }
+        }
+
+        let range = Range::new(111_111, 111_111);
+        assert_eq!(range.tally::<usize>(&Rule::PART2).unwrap(), Tally { count: 1, sum: 111_111 });
+        assert_eq!(Rule::PART2.coefficients(6), vec![(2, 1), (3, 1), (6, -1)]);
+        assert_eq!(Rule::PART2.coefficients(30), vec![(2, 1), (3, 1), (5, 1), (6, -1), (10, -1), (15, -1), (30, 1)]);
+        assert_eq!(Rule::PART1.coefficients(7), vec![]);
+    }
+
+    #[test]
+    // Ranges up to 10^18 are added up without listing them.
+    fn test_tally_wide() {
+        // Every id of 2L digits is some L digit kernel twice.
+        let mut count = 0;
+        let mut sum = 0;
+        for l in 1..=9 {
+            let (first, last) = (10_u128.pow(l - 1), 10_u128.pow(l) - 1);
+            count += last - first + 1;
+            sum += (10_u128.pow(l) + 1) * (first + last) * (last - first + 1) / 2;
+        }
+        let range = Range::new(1, 10_usize.pow(18));
+        assert_eq!(range.tally::<u128>(&Rule::PART1).unwrap(), Tally { count, sum });
+        assert!(range.tally::<usize>(&Rule::PART1).is_err());
+
+        let tally = range.tally::<u128>(&Rule::PART2).unwrap();
+        assert!(tally.count > count && tally.sum > sum);
+
+        let range = Range::new(10_usize.pow(19), usize::MAX);
+        assert_eq!(range.tally::<u128>(&Rule::PART1).unwrap().count, 1_844_674_407 - 1_000_000_000 + 1);
+    }
+
+    #[test]
+    // Ids past 2^53, where an f64 can't hold every integer, and past 64 and 128 bits.
+    fn test_exact() {
+        // 9007199290071993 is just past an id, but as an f64 it's the id.
+        let range = Range::new(9_007_199_290_071_993, 9_007_199_390_071_993);
+        assert_eq!(range.invalids(&Rule::PART1).collect::<Vec<_>>(), vec![big(9_007_199_390_071_993)]);
+        assert_eq!(range.tally::<usize>(&Rule::PART1).unwrap(), Tally { count: 1, sum: 9_007_199_390_071_993 });
+
+        let d = Day2::new();
+        let wide = "12345678901231234567890122-12345678901231234567890124\n";
+        let id: BigUint = "12345678901231234567890123".parse().unwrap();
+        assert_eq!(d.try_part1(wide), Err(SolveError::overflow(2, "sum of invalid ids")));
+        set_policy(Policy::Wide);
+        assert_eq!(d.try_part1(wide), Ok(Answer::Big(id.clone())));
+        assert_eq!(d.solve_with(2, "listed", wide), Ok(Answer::Big(id)));
+
+        let ones = "1".repeat(40);
+        let huge = format!("{ones}-{ones}\n");
+        assert_eq!(d.try_part2(&huge), Err(SolveError::overflow(2, "sum of invalid ids")));
+        set_policy(Policy::Big);
+        assert_eq!(d.try_part1(&huge), Ok(Answer::Big(ones.parse().unwrap())));
+        assert_eq!(d.try_part2(&huge), Ok(Answer::Big(ones.parse().unwrap())));
+        set_policy(Policy::Checked);
+
+        // Listing and the formula agree on long ids.
+        let range = Range::parse(1, "123412341234123412341233-123412341235123412341236").unwrap();
+        let listed: Vec<BigUint> = range.invalids(&Rule::PART2).collect();
+        let tally = range.tally::<BigUint>(&Rule::PART2).unwrap();
+        assert_eq!(tally, Tally { count: BigUint::from(listed.len()), sum: listed.iter().sum() });
+        assert!(listed.contains(&"123412341234123412341234".parse().unwrap()));
+        assert!(listed.contains(&"123412341235123412341235".parse().unwrap()));
+
+        let range = Range::parse(1, "1-99999999999999999999999999999999").unwrap();
+        assert!(range.tally_listed::<BigUint>(&Rule::PART1).is_err());
+        assert!(range.tally::<BigUint>(&Rule::PART1).is_ok());
+
+        assert!(Range::parse(1, "1_0-20").is_err());
+        assert!(Range::parse(1, "+10-20").is_err());
+    }
+
+    #[test]
+    // Other bases and repeat counts, by the formula and by listing, match checking every id.
+    fn test_rules() {
+        let rules = [
+            Rule::new(2, 2, usize::MAX, false).unwrap(),
+            Rule::new(3, 2, 2, true).unwrap(),
+            Rule::new(16, 3, 5, false).unwrap(),
+            Rule::new(10, 2, 3, true).unwrap(),
+            Rule::new(10, 4, usize::MAX, true).unwrap(),
+            Rule::new(36, 2, 6, false).unwrap(),
+        ];
+        let mut rng = Rng::new(47);
+        for _ in 0..100 {
+            let low = rng.range(1, 1_000_000);
+            let high = low + rng.range(0, 5000);
+            let range = Range::new(low, high);
+            for rule in &rules {
+                let expected: Vec<BigUint> = (low..=high).map(big).filter(|id| { rule.is_invalid(id) }).collect();
+                assert_eq!(range.invalids(rule).collect::<Vec<_>>(), expected, "range {low}-{high}, {rule}");
+
+                let tally = Tally { count: expected.len(), sum: expected.iter().map(|id| { usize::try_from(id).unwrap() }).sum() };
+                assert_eq!(range.tally::<usize>(rule).unwrap(), tally, "range {low}-{high}, {rule}");
+            }
+        }
+
+        // 0b101101 is 0b101 twice; 0b111111 is 0b1 six times, so only shortest kernels of 6 count.
+        let binary = Rule::new(2, 2, 2, false).unwrap();
+        assert!(binary.is_invalid(&big(0b101101)));
+        assert!(!Rule::new(2, 2, 2, true).unwrap().is_invalid(&big(0b111111)));
+        assert!(Rule::new(2, 6, 6, true).unwrap().is_invalid(&big(0b111111)));
+
+        // The example under part 2's rule, but only counting ids exactly twice over.
+        let d = Day2::with_rules(binary, Rule::new(10, 2, 2, true).unwrap());
+        assert_eq!(d.part1("5-10,40-50"), Answer::Numeric(10 + 45));
+        assert_eq!(d.count_and_sum(EXAMPLE1, 2), Ok((Answer::Numeric(7), Answer::Numeric(1227775554 - 222222))));
+        assert!(d.count_and_sum(EXAMPLE1, 3).is_err());
+
+        assert_eq!(Day2::new().count_and_sum(EXAMPLE1, 2), Ok((Answer::Numeric(13), Answer::Numeric(4174379265))));
+
+        assert!(Rule::new(1, 2, 2, false).is_err());
+        assert!(Rule::new(37, 2, 2, false).is_err());
+        assert!(Rule::new(10, 1, 2, false).is_err());
+        assert!(Rule::new(10, 3, 2, false).is_err());
+
+        assert_eq!(Rule::PART1.to_string(), "base 10, 2 repeats");
+        assert_eq!(Rule::PART2.to_string(), "base 10, 2 or more repeats");
+        assert_eq!(Rule::new(16, 2, 4, true).unwrap().to_string(), "base 16, 2 to 4 repeats, shortest kernel");
+    }
+
+    #[test]
+    // Palindromes, non-decreasing digits and patterns, counted digit by digit, match checking
+    // every id.
+    fn test_predicates() {
+        let predicates: Vec<Box<dyn Predicate>> = vec![
+            Box::new(Palindromes::new(10).unwrap()),
+            Box::new(Palindromes::new(2).unwrap()),
+            Box::new(Palindromes::new(16).unwrap()),
+            Box::new(NonDecreasing::new(10).unwrap()),
+            Box::new(NonDecreasing::new(3).unwrap()),
+            Box::new(Pattern::new(10, "1[0-9]*7").unwrap()),
+            Box::new(Pattern::new(10, "(12)+|9+").unwrap()),
+            Box::new(Pattern::new(16, "[a-f][0-9a-f]?").unwrap()),
+            Box::new(Rule::PART2),
+        ];
+        let mut rng = Rng::new(49);
+        for _ in 0..100 {
+            let low = rng.range(0, 1_000_000);
+            let high = low + rng.range(0, 3000);
+            let range = Range::new(low, high);
+            for predicate in &predicates {
+                let ids: Vec<usize> = (max(low, 1)..=high).filter(|id| { predicate.matches(&big(*id)) }).collect();
+                let tally = Tally { count: big(ids.len()), sum: big(ids.iter().sum()) };
+                assert_eq!(range.tally_with(predicate.as_ref()).unwrap(), tally, "range {low}-{high}, {predicate}");
+            }
+        }
+
+        // Far more ids than could be checked one at a time.
+        let range = Range::parse(1, &format!("1-{}", "9".repeat(30))).unwrap();
+        let palindromes = range.tally_with(&Palindromes::new(10).unwrap()).unwrap();
+        assert_eq!(palindromes.count, BigUint::from(10_usize).pow(15) * 2_usize - 2_usize);
+        assert_eq!(range.tally_with(&NonDecreasing::new(10).unwrap()).unwrap().count, big(211_915_131));
+        assert_eq!(range.tally_with(&Pattern::new(10, "[1-9]0*").unwrap()).unwrap().count, big(9 * 30));
+
+        let palindromes = Palindromes::new(10).unwrap();
+        assert_eq!(Day2::new().count_and_sum_matching(EXAMPLE1, &palindromes), Ok((Answer::Numeric(8), Answer::Numeric(224566))));
+        assert_eq!(Day2::new().count_and_sum_matching(EXAMPLE1, &Rule::PART2), Day2::new().count_and_sum(EXAMPLE1, 2));
+
+        assert!(Palindromes::new(1).is_err());
+        assert!(NonDecreasing::new(37).is_err());
+        assert!(Pattern::new(10, "(1").is_err());
+        assert_eq!(Pattern::new(10, "1+").unwrap().to_string(), "base 10, matching '1+'");
+        assert_eq!(NonDecreasing::new(8).unwrap().to_string(), "base 8, non-decreasing digits");
+    }
+
+    #[test]
+    // Overlapping and backwards ranges are counted as given, merged, or rejected.
+    fn test_overlaps() {
+        let text = "11-22,15-30\n40-33\n";
+        let d = Day2::new();
+        assert_eq!(d.part1(text), Answer::Numeric(11 + 22 + 22));
+        assert_eq!(d.lint(text), Some(vec![SolveError::parse(1, "Range 15-30 overlaps 11-22 on line 1."),
+                                           SolveError::parse(2, "Range 40-33 runs backwards.")]));
+
+        let merged = Day2::new().with_overlaps(Overlaps::Merge);
+        assert_eq!(merged.part1(text), Answer::Numeric(11 + 22 + 33));
+        assert_eq!(merged.solve_stream(1, &mut text.as_bytes()), None);
+        let ranges: Vec<(usize, BigUint, BigUint)> = merged.breakdown(text, &Rule::PART1).unwrap().into_iter()
+            .map(|r| { (r.line, r.low, r.high) })
+            .collect();
+        assert_eq!(ranges, vec![(1, big(11), big(30)), (2, big(33), big(40))]);
+
+        let rejected = Day2::new().with_overlaps(Overlaps::Reject);
+        assert_eq!(rejected.try_part1(text), Err(SolveError::parse(1, "Range 15-30 overlaps 11-22 on line 1.")));
+        assert_eq!(rejected.try_part1("40-33\n"), Err(SolveError::parse(1, "Range 40-33 runs backwards.")));
+        assert_eq!(rejected.part2(EXAMPLE1), Answer::Numeric(4174379265));
+        assert_eq!(d.lint(EXAMPLE1), Some(vec![]));
+
+        // Merged ranges count each id once, however the ranges overlap.
+        let mut rng = Rng::new(50);
+        for _ in 0..50 {
+            let mut ranges = Vec::new();
+            let mut ids = HashSet::new();
+            for _ in 0..rng.range(1, 6) {
+                let (a, b) = (rng.range(1, 20_000), rng.range(1, 20_000));
+                let (low, high) = (min(a, b), max(a, b));
+                ids.extend((low..=high).filter(|id| { is_repeat(*id, 2, false) }));
+                ranges.push(if rng.range(0, 1) == 0 { format!("{low}-{high}") } else { format!("{high}-{low}") });
+            }
+            let text = ranges.join(",") + "\n";
+            assert_eq!(merged.count_and_sum(&text, 2), Ok((Answer::Numeric(ids.len()), Answer::Numeric(ids.iter().sum()))), "{text}");
+            assert_eq!(merged.lint(&text).unwrap().is_empty(), rejected.try_part2(&text).is_ok(), "{text}");
+        }
+    }
+
+    #[test]
+    // Each range's share of the invalid ids, then the totals.
+    fn test_inspect() {
+        let d = Day2::new().with_overlaps(Overlaps::Merge);
+        assert_eq!(d.inspect("11-22,15-30\n40-33\n"), Some(Ok(String::from("\
+2 ranges, overlaps merged
+  line 1: 11-30: part 1 2 ids, sum 33; part 2 2 ids, sum 33
+  line 2: 33-40: part 1 1 ids, sum 33; part 2 1 ids, sum 33
+total: part 1 3 ids, sum 66; part 2 3 ids, sum 66
+"))));
+        assert!(d.inspect("11-x\n").unwrap().is_err());
+    }
+
+    #[test]
+    // The listed strategy gives the same answers.
+    fn test_strategies() {
+        let d = Day2::new();
+        assert_eq!(d.solve_with(1, "listed", EXAMPLE1), Ok(Answer::Numeric(1227775554)));
+        assert_eq!(d.solve_with(2, "listed", EXAMPLE1), Ok(Answer::Numeric(4174379265)));
+    }
+
+    #[test]
+    // Streaming a range at a time gives the same answers, with the ranges on one line or many.
+    fn test_stream() {
+        let d = Day2::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let lines = text.replace(',', "\n");
+            for input in [&text, &lines] {
+                assert_eq!(d.solve_stream(1, &mut input.as_bytes()), Some(d.try_part1(input)));
+                assert_eq!(d.solve_stream(2, &mut input.as_bytes()), Some(d.try_part2(input)));
+            }
+        }
+
+        assert_eq!(d.solve_stream(1, &mut "11-22\n95-x\n".as_bytes()), Some(Err(SolveError::parse(2, "Bad range '95-x'"))));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day2::new(), &[EXAMPLE1]);
+    }
 }
@@ -1,19 +1,28 @@
-use crate::day::{Day, Answer};
+use std::io::BufRead;
+
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError, for_each_line};
+use crate::rng::Rng;
+
+const DAY: usize = 3;
 
 struct Bank {
     cells: Vec<usize>,
 }
 
 impl Bank {
-    fn from_line(line: &str) -> Bank {
+    // Parse a bank's cells, one digit each.  Lines and columns are numbered from 1.
+    fn from_line(line_no: usize, line: &str) -> Result<Bank, SolveError> {
         let mut cells = Vec::new();
-        for c in line.chars() {
-            if let Some(n) = c.to_digit(10) {
-                cells.push(n as usize);
-            }
+        for (col, c) in line.chars().enumerate() {
+            let n = c.to_digit(10)
+                .ok_or_else(|| { SolveError::parse(line_no, format!("Bad battery '{c}' at column {}", col + 1)) })?;
+            cells.push(n as usize);
         }
 
-        Bank { cells }
+        Ok(Bank { cells })
     }
 
     fn max_jolts(&self, n: usize) -> usize {
@@ -49,17 +58,30 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut banks = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
-            let bank = Bank::from_line(line);
+            let bank = Bank::from_line(line_no + 1, line)?;
             banks.push(bank);
         }
 
-        Input { banks }
+        Ok(Input { banks })
+    }
+
+    // Random input in the format read() accepts: `size` banks of 12 to 100 cells rated 1-9.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..size {
+            for _ in 0..rng.range(12, 100) {
+                text.push_str(&rng.range(1, 9).to_string());
+            }
+            text.push('\n');
+        }
+
+        text
     }
 }
 
@@ -70,42 +92,82 @@ impl Day3 {
     pub const fn new() -> Self {
         Self { }
     }
-}
 
-impl Day for Day3 {
+    // Add the best joltage from a bank, turning on n batteries, to a sum.
+    fn add_jolts<T: Arith>(sum: &mut T, line_no: usize, bank: &Bank, n: usize) -> Result<(), SolveError> {
+        if bank.cells.len() < n {
+            return Err(SolveError::parse(line_no, format!("Bank has fewer than {n} batteries")));
+        }
 
-    // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        // Read input file into Input struct
-        let input = Input::read(text);
+        *sum = sum.checked_add(&T::from_usize(bank.max_jolts(n))).ok_or(SolveError::overflow(DAY, "sum of joltages"))?;
 
-        let sum_jolts = input.banks.iter()
-            .map(|b| {
-                b.max_jolts(2) as usize
-            })
-            .sum();
+        Ok(())
+    }
 
-        Answer::Numeric(sum_jolts)
+    // Sum of the best joltage from each bank, turning on n batteries per bank.
+    fn sum_jolts<T: Arith>(banks: &[Bank], n: usize) -> Result<Answer, SolveError> {
+        let mut sum_jolts = T::from_usize(0);
+        for (line_no, b) in banks.iter().enumerate() {
+            Day3::add_jolts(&mut sum_jolts, line_no + 1, b, n)?;
+        }
+
+        Ok(sum_jolts.into_answer())
+    }
+
+    fn stream_jolts<T: Arith>(reader: &mut dyn BufRead, n: usize) -> Result<Answer, SolveError> {
+        let mut sum_jolts = T::from_usize(0);
+        for_each_line(reader, |line_no, line| {
+            Day3::add_jolts(&mut sum_jolts, line_no, &Bank::from_line(line_no, line)?, n)
+        })?;
+
+        Ok(sum_jolts.into_answer())
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn solve(text: &str, n: usize) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
-        let sum_jolts = input.banks.iter()
-            .map(|b| {
-                b.max_jolts(12) as usize
-            })
-            .sum();
+        arith::widen(|| { Day3::sum_jolts::<usize>(&input.banks, n) },
+                     || { Day3::sum_jolts::<u128>(&input.banks, n) },
+                     || { Day3::sum_jolts::<BigUint>(&input.banks, n) })
+    }
+}
 
-        Answer::Numeric(sum_jolts)
+impl Day for Day3 {
+
+    // Compute Part 1 solution
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
+        Day3::solve(text, 2)
+    }
+
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
+        Day3::solve(text, 12)
+    }
+
+    fn solve_stream(&self, part: usize, reader: &mut dyn BufRead) -> Option<Result<Answer, SolveError>> {
+        let n = match part {
+            1 => 2,
+            2 => 12,
+            _ => return None,
+        };
+
+        Some(arith::widest(reader,
+                           |r| { Day3::stream_jolts::<usize>(r, n) },
+                           |r| { Day3::stream_jolts::<u128>(r, n) },
+                           |r| { Day3::stream_jolts::<BigUint>(r, n) }))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day3::{Day3, Input};
-    use crate::day::{Day, Answer};
+    use crate::day3::{Bank, Day3, Input};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 987654321111111
@@ -117,7 +179,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.banks.len(), 4);
         assert_eq!(input.banks[0].cells.len(), 15);
@@ -126,7 +188,7 @@ mod test {
 
     #[test]
     fn test_max_jolts() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.banks[0].max_jolts(2), 98);
         assert_eq!(input.banks[1].max_jolts(2), 89);
@@ -136,7 +198,7 @@ mod test {
 
     #[test]
     fn test_max_jolts2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.banks[0].max_jolts(12), 987654321111);
         assert_eq!(input.banks[1].max_jolts(12), 811111111119);
@@ -160,5 +222,85 @@ mod test {
         let d = Day3::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(3121910778619));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Twelve batteries always beat two.
+    fn test_generate() {
+        let d = Day3::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!(input.banks.len(), 20);
+            assert!(input.banks.iter().all(|b| { b.cells.len() >= 12 }));
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p1 < p2);
+        }
+    }
+
+    #[test]
+    // Streaming a bank at a time gives the same answers.
+    fn test_stream() {
+        let d = Day3::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            assert_eq!(d.solve_stream(1, &mut text.as_bytes()), Some(d.try_part1(&text)));
+            assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(d.try_part2(&text)));
+        }
+
+        let short = "987654321111111\n12345\n";
+        assert_eq!(d.solve_stream(2, &mut short.as_bytes()), Some(d.try_part2(short)));
+        assert!(matches!(d.solve_stream(2, &mut short.as_bytes()), Some(Err(SolveError::Parse { line: 2, .. }))));
+
+        let bad = "987654321111111\n8111x1111111119\n";
+        assert_eq!(d.solve_stream(1, &mut bad.as_bytes()), Some(d.try_part1(bad)));
+    }
+
+    #[test]
+    // Anything but a digit is an error, with where it is.
+    fn test_bad_battery() {
+        let d = Day3::new();
+        assert_eq!(d.try_part1("987654321111111\n8111x1111111119\n"),
+                   Err(SolveError::parse(2, "Bad battery 'x' at column 5")));
+        assert_eq!(d.try_part2("98765432111111 1\n"), Err(SolveError::parse(1, "Bad battery ' ' at column 15")));
+        assert!(Input::read("12\n3-4\n").is_err());
+    }
+
+    // Best joltage from choosing `n` of the cells, trying every subsequence.
+    fn exhaustive_jolts(cells: &[usize], n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+
+        (0..cells.len())
+            .filter(|first| { cells.len() - first >= n })
+            .map(|first| {
+                cells[first] * 10_usize.pow(n as u32 - 1) + exhaustive_jolts(&cells[first+1..], n - 1)
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    // Compare max_jolts() against trying every subsequence of small random banks.
+    fn test_oracle() {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let len = rng.range(12, 15);
+            let cells: Vec<usize> = (0..len).map(|_| { rng.range(1, 9) }).collect();
+            let bank = Bank { cells: cells.clone() };
+
+            for n in [1, 2, 3, 12] {
+                assert_eq!(bank.max_jolts(n), exhaustive_jolts(&cells, n), "bank {cells:?}, n {n}");
+            }
+        }
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day3::new(), &[EXAMPLE1]);
+    }
 }
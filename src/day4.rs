@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-use crate::day::{Day, Answer};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::vis::{Style, Svg};
 
 // A representation of the puzzle inputs.
 struct Input {
@@ -10,7 +12,7 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut rows: isize = 0;
         let mut cols: isize = 0;
@@ -28,7 +30,9 @@ impl Input {
                         occupied.insert((col, rows));
                         col += 1;
                     }
-                    _ => ()
+                    _ => {
+                        return Err(SolveError::parse(rows as usize + 1, format!("Bad cell '{c}' at column {}", col + 1)));
+                    }
                 }
                 if col > cols {
                     cols = col;
@@ -39,7 +43,20 @@ impl Input {
             // Process one line
         }
 
-        Input { rows, cols, occupied }
+        Ok(Input { rows, cols, occupied })
+    }
+
+    // Random input in the format read() accepts: a `size` x `size` grid, about 2/3 rolls.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..size {
+            for _ in 0..size {
+                text.push(if rng.chance(2, 3) { '@' } else { '.' });
+            }
+            text.push('\n');
+        }
+
+        text
     }
 
     fn neighbors(&self, loc: &(isize, isize)) -> usize {
@@ -56,6 +73,56 @@ impl Input {
 
         count
     }
+
+    // Remove every roll with fewer than four neighbors, one at a time in column order, so
+    // later ones see the earlier ones gone.  Returns those removed.
+    fn remove_accessible(&mut self) -> Vec<(isize, isize)> {
+        let mut removed = Vec::new();
+        for x in 0..self.cols {
+            for y in 0..self.rows {
+                if self.occupied.contains(&(x, y)) && (self.neighbors(&(x, y)) < 4) {
+                    // remove this roll
+                    self.occupied.remove(&(x, y));
+                    removed.push((x, y));
+                }
+            }
+        }
+
+        removed
+    }
+
+    // The grid, with rolls just removed marked 'x'.
+    fn draw(&self, style: Style, removed: &HashSet<(isize, isize)>) -> String {
+        const CELL: f64 = 8.0;
+
+        match style {
+            Style::Ascii => {
+                let mut text = String::new();
+                for y in 0..self.rows {
+                    for x in 0..self.cols {
+                        text.push(match (self.occupied.contains(&(x, y)), removed.contains(&(x, y))) {
+                            (true, _) => '@',
+                            (false, true) => 'x',
+                            (false, false) => '.',
+                        });
+                    }
+                    text.push('\n');
+                }
+
+                text
+            }
+            Style::Svg => {
+                let mut svg = Svg::new(self.cols as f64 * CELL, self.rows as f64 * CELL);
+                svg.rect(0.0, 0.0, self.cols as f64 * CELL, self.rows as f64 * CELL, "#f6f8fa");
+                for &(x, y) in self.occupied.iter().chain(removed.iter()) {
+                    let fill = if removed.contains(&(x, y)) { "#e15759" } else { "#8c6d31" };
+                    svg.rect(x as f64 * CELL + 1.0, y as f64 * CELL + 1.0, CELL - 2.0, CELL - 2.0, fill);
+                }
+
+                svg.finish()
+            }
+        }
+    }
 }
 
 pub struct Day4 {
@@ -70,9 +137,9 @@ impl Day4 {
 impl Day for Day4 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
         let mut count = 0;
         for x in 0..input.cols {
@@ -83,39 +150,56 @@ impl Day for Day4 {
             }
         }
 
-        Answer::Numeric(count)
+        Ok(Answer::Numeric(count))
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let mut input = Input::read(text);
-        let mut removed = true;
+        let mut input = Input::read(text)?;
         let mut num_removed = 0;
 
-        while removed {
-            removed = false;
+        loop {
+            let removed = input.remove_accessible();
+            if removed.is_empty() {
+                break;
+            }
+            num_removed += removed.len();
+        }
+
+        Ok(Answer::Numeric(num_removed))
+    }
 
-            for x in 0..input.cols {
-                for y in 0..input.rows {
-                    if input.occupied.contains(&(x, y)) && (input.neighbors(&(x, y)) < 4) {
-                        // remove this roll
-                        input.occupied.remove(&(x, y));
-                        removed = true;
-                        num_removed += 1;
-                    }
-                }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // The grid before any rolls are removed, then after each pass of part 2.
+    fn visualize(&self, text: &str, style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        let mut input = match Input::read(text) {
+            Ok(input) => input,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut frames = vec![input.draw(style, &HashSet::new())];
+        loop {
+            let removed: HashSet<(isize, isize)> = input.remove_accessible().into_iter().collect();
+            if removed.is_empty() {
+                break;
             }
+            frames.push(input.draw(style, &removed));
         }
 
-        Answer::Numeric(num_removed)
+        Some(Ok(frames))
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day4::{Day4, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
+    use crate::vis::Style;
     
     const EXAMPLE1: &str = "\
 ..@@.@@@@.
@@ -133,7 +217,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.rows, 10);
         assert_eq!(input.cols, 10);
@@ -143,7 +227,7 @@ mod test {
 
     #[test]
     fn test_neighbors() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.neighbors(&(0, 0)), 2);
         assert_eq!(input.neighbors(&(1, 0)), 4);
@@ -166,5 +250,51 @@ mod test {
         let d = Day4::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(43));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  The first pass removes no more than all the passes.
+    fn test_generate() {
+        let d = Day4::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!((input.rows, input.cols), (20, 20));
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p1 <= p2);
+            assert!(p2 <= input.occupied.len());
+        }
+    }
+
+    #[test]
+    // Anything but a roll or an empty space is an error, with where it is.
+    fn test_bad_cell() {
+        let d = Day4::new();
+        assert_eq!(d.try_part1("..@@\n.@x@\n"), Err(SolveError::parse(2, "Bad cell 'x' at column 3")));
+        assert_eq!(d.try_part2("@@ @\n"), Err(SolveError::parse(1, "Bad cell ' ' at column 3")));
+        assert_eq!(d.visualize("@#\n", Style::Ascii), Some(Err(SolveError::parse(1, "Bad cell '#' at column 2"))));
+    }
+
+    #[test]
+    // One frame per pass, the first with no rolls removed yet.
+    fn test_visualize() {
+        let d = Day4::new();
+        let frames = d.visualize(EXAMPLE1, Style::Ascii).unwrap().unwrap();
+        assert_eq!(frames[0], EXAMPLE1);
+        assert!(frames[1].starts_with("..xx.xxxx.\nx@@.x.x.xx\n"));
+        let removed: usize = frames.iter().map(|f| { f.matches('x').count() }).sum();
+        assert_eq!(removed, 43);
+
+        let svg = d.visualize(EXAMPLE1, Style::Svg).unwrap().unwrap();
+        assert_eq!(svg.len(), frames.len());
+        assert!(svg[0].starts_with("<svg "));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day4::new(), &[EXAMPLE1]);
+    }
 }
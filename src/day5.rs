@@ -1,6 +1,14 @@
 use std::cmp::{max, min};
 
-use crate::day::{Day, Answer};
+use std::io::BufRead;
+
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError, for_each_line};
+use crate::rng::Rng;
+
+const DAY: usize = 5;
 
 #[derive(Eq, PartialEq, Ord, PartialOrd)]
 struct FreshRange {
@@ -22,33 +30,66 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let mut fresh_ranges = Vec::new();
         let mut ingredients = Vec::new();
         let mut in_ranges = true;
 
         // Process fresh ranges
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             if in_ranges {
                 if !line.contains("-") {
                     // separator line
                     in_ranges = false;
                 }
                 else {
-                    let mut numbers = line.split("-");
-                    let low = numbers.nth(0).unwrap().parse::<usize>().unwrap();
-                    let high = numbers.nth(0).unwrap().parse::<usize>().unwrap();
-                    fresh_ranges.push(FreshRange{low, high});
+                    fresh_ranges.push(Input::parse_range(line_no + 1, line)?);
                 }
             }
             else {
-                let ingredient = line.parse::<usize>().unwrap();
-                ingredients.push(ingredient);
+                ingredients.push(Input::parse_ingredient(line_no + 1, line)?);
             }
         }
 
-        Input { fresh_ranges, ingredients }
+        Ok(Input { fresh_ranges, ingredients })
+    }
+
+    // Parse a fresh range, e.g. "3-5".  Lines are numbered from 1.
+    fn parse_range(line_no: usize, line: &str) -> Result<FreshRange, SolveError> {
+        let bad_line = || { SolveError::parse(line_no, format!("Bad line '{line}'")) };
+
+        let (low, high) = line.split_once("-").ok_or_else(bad_line)?;
+        let low = low.parse::<usize>().map_err(|_| { bad_line() })?;
+        let high = high.parse::<usize>().map_err(|_| { bad_line() })?;
+        if low > high {
+            return Err(SolveError::parse(line_no, format!("Reversed range '{line}'")));
+        }
+
+        Ok(FreshRange{low, high})
+    }
+
+    fn parse_ingredient(line_no: usize, line: &str) -> Result<usize, SolveError> {
+        line.parse::<usize>().map_err(|_| { SolveError::parse(line_no, format!("Bad line '{line}'")) })
+    }
+
+    // Random input in the format read() accepts: `size` fresh ranges (at least one), which may
+    // overlap, then `size` ingredient ids.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const MAX_ID: usize = 1_000_000_000_000;
+
+        let mut text = String::new();
+        for _ in 0..max(size, 1) {
+            let low = rng.range(1, MAX_ID);
+            let high = low + rng.range(0, MAX_ID / 100);
+            text.push_str(&format!("{low}-{high}\n"));
+        }
+        text.push('\n');
+        for _ in 0..size {
+            text.push_str(&format!("{}\n", rng.range(1, MAX_ID)));
+        }
+
+        text
     }
 
 
@@ -61,6 +102,64 @@ impl Input {
         
         false
     }
+
+    // Number of ids in at least one fresh range, merging overlapping ranges as it goes.
+    fn fresh_id_count(mut self) -> Result<Answer, SolveError> {
+        self.merge_ranges();
+
+        arith::widen(|| { Input::count_ids::<usize>(&self.fresh_ranges) },
+                     || { Input::count_ids::<u128>(&self.fresh_ranges) },
+                     || { Input::count_ids::<BigUint>(&self.fresh_ranges) })
+    }
+
+    // Merge overlapping fresh ranges until none overlap, then sort them.
+    fn merge_ranges(&mut self) {
+        let mut reduced = true;
+        while reduced {
+            reduced = false;
+            'outer:
+            for i in 0 .. self.fresh_ranges.len().saturating_sub(1) {
+                for j in i+1..self.fresh_ranges.len() {
+                    // See if we can merge ranges i and j
+                    if (self.fresh_ranges[i].low >= self.fresh_ranges[j].low &&
+                        self.fresh_ranges[i].low <= self.fresh_ranges[j].high) ||
+                       (self.fresh_ranges[i].high >= self.fresh_ranges[j].low &&
+                        self.fresh_ranges[i].high <= self.fresh_ranges[j].high) ||
+                       (self.fresh_ranges[j].low >= self.fresh_ranges[i].low &&
+                        self.fresh_ranges[j].low <= self.fresh_ranges[i].high) ||
+                       (self.fresh_ranges[j].high >= self.fresh_ranges[i].low &&
+                        self.fresh_ranges[j].high <= self.fresh_ranges[i].high) {
+                            // They overlap.
+                            let new_low = min(self.fresh_ranges[i].low, self.fresh_ranges[j].low);
+                            let new_high = max(self.fresh_ranges[i].high, self.fresh_ranges[j].high);
+
+                            // Delete former ranges
+                            self.fresh_ranges.remove(j);
+                            self.fresh_ranges.remove(i);
+                            self.fresh_ranges.push(FreshRange{ low: new_low, high: new_high });
+                            reduced = true;
+                            break 'outer;
+                        }
+                }
+            }
+        }
+
+        self.fresh_ranges.sort();
+    }
+
+    // Count all the values in all the ranges, which mustn't overlap.
+    fn count_ids<T: Arith>(ranges: &[FreshRange]) -> Result<Answer, SolveError> {
+        let overflow = || { SolveError::overflow(DAY, "count of fresh ids") };
+
+        let mut num_fresh = T::from_usize(0);
+        for r in ranges {
+            // A range can hold every usize, one more than fits in one.
+            let size = T::from_usize(r.high - r.low).checked_add(&T::from_usize(1)).ok_or_else(overflow)?;
+            num_fresh = num_fresh.checked_add(&size).ok_or_else(overflow)?;
+        }
+
+        Ok(num_fresh.into_answer())
+    }
 }
 
 pub struct Day5 {
@@ -75,59 +174,82 @@ impl Day5 {
 impl Day for Day5 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
         let num_fresh = input.ingredients.iter()
             .filter(|ingredient| { input.is_fresh(**ingredient) })
             .count();
 
-        Answer::Numeric(num_fresh)
+        Ok(Answer::Numeric(num_fresh))
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let mut input = Input::read(text);
-        let mut reduced = true;
-        while reduced {
-            reduced = false;
-            'outer:
-            for i in 0 .. input.fresh_ranges.len()-1 {
-                for j in i+1..input.fresh_ranges.len() {
-                    // See if we can merge ranges i and j
-                    if (input.fresh_ranges[i].low >= input.fresh_ranges[j].low &&
-                        input.fresh_ranges[i].low <= input.fresh_ranges[j].high) ||
-                       (input.fresh_ranges[i].high >= input.fresh_ranges[j].low &&
-                        input.fresh_ranges[i].high <= input.fresh_ranges[j].high) ||
-                       (input.fresh_ranges[j].low >= input.fresh_ranges[i].low &&
-                        input.fresh_ranges[j].low <= input.fresh_ranges[i].high) ||
-                       (input.fresh_ranges[j].high >= input.fresh_ranges[i].low &&
-                        input.fresh_ranges[j].high <= input.fresh_ranges[i].high) {
-                            // They overlap.
-                            let new_low = min(input.fresh_ranges[i].low, input.fresh_ranges[j].low);
-                            let new_high = max(input.fresh_ranges[i].high, input.fresh_ranges[j].high);
+        let input = Input::read(text)?;
 
-                            // Delete former ranges
-                            input.fresh_ranges.remove(j);
-                            input.fresh_ranges.remove(i);
-                            input.fresh_ranges.push(FreshRange{ low: new_low, high: new_high });
-                            reduced = true;
-                            break 'outer;
-                        }
+        input.fresh_id_count()
+    }
+
+    // Reads the fresh ranges into memory, then streams the ingredients.
+    fn solve_stream(&self, part: usize, reader: &mut dyn BufRead) -> Option<Result<Answer, SolveError>> {
+        if part != 1 && part != 2 {
+            return None;
+        }
+
+        let mut input = Input { fresh_ranges: Vec::new(), ingredients: Vec::new() };
+        let mut in_ranges = true;
+        let mut num_fresh = 0;
+        let result = for_each_line(reader, |line_no, line| {
+            if in_ranges {
+                if !line.contains("-") {
+                    // separator line
+                    in_ranges = false;
+                }
+                else {
+                    input.fresh_ranges.push(Input::parse_range(line_no, line)?);
                 }
             }
-        }
+            else if input.is_fresh(Input::parse_ingredient(line_no, line)?) {
+                num_fresh += 1;
+            }
+            Ok(())
+        });
 
-        input.fresh_ranges.sort();
+        Some(result.and_then(|_| {
+            if part == 1 {
+                Ok(Answer::Numeric(num_fresh))
+            }
+            else {
+                input.fresh_id_count()
+            }
+        }))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
 
-        // Count all the values in all the ranges
-        let num_fresh = input.fresh_ranges.iter()
-            .map(|r| { r.high - r.low + 1 })
-            .sum();
+    // The ranges as read, then merged the way part 2 merges them.
+    fn inspect(&self, text: &str) -> Option<Result<String, SolveError>> {
+        let mut input = match Input::read(text) {
+            Ok(input) => input,
+            Err(e) => return Some(Err(e)),
+        };
 
-        Answer::Numeric(num_fresh)
+        let mut text = format!("{} fresh ranges, {} ingredients\n", input.fresh_ranges.len(), input.ingredients.len());
+        for r in &input.fresh_ranges {
+            text.push_str(&format!("  {}-{}\n", r.low, r.high));
+        }
+        input.merge_ranges();
+        text.push_str(&format!("merged into {} ranges:\n", input.fresh_ranges.len()));
+        for r in &input.fresh_ranges {
+            text.push_str(&format!("  {}-{}\n", r.low, r.high));
+        }
+
+        Some(Ok(text))
     }
 }
 
@@ -135,6 +257,10 @@ impl Day for Day5 {
 mod test {
     use crate::day5::{Day5, Input};
     use crate::day::{Day, Answer};
+    use std::collections::HashSet;
+
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 3-5
@@ -153,7 +279,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.fresh_ranges.len(), 4);
         assert_eq!(input.ingredients.len(), 6);
@@ -161,7 +287,7 @@ mod test {
 
     #[test]
     fn test_freshness() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert!(!input.is_fresh(1));
         assert!(input.is_fresh(5));
@@ -171,6 +297,22 @@ mod test {
         assert!(!input.is_fresh(32));
     }
 
+    #[test]
+    // The overlapping ranges merge into two.
+    fn test_inspect() {
+        let d = Day5::new();
+        assert_eq!(d.inspect(EXAMPLE1), Some(Ok(String::from("\
+4 fresh ranges, 6 ingredients
+  3-5
+  10-14
+  16-20
+  12-18
+merged into 2 ranges:
+  3-5
+  10-20
+"))));
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
@@ -187,5 +329,66 @@ mod test {
         let d = Day5::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(14));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.
+    fn test_generate() {
+        let d = Day5::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!(input.fresh_ranges.len(), 20);
+            assert_eq!(input.ingredients.len(), 20);
+
+            let Answer::Numeric(p1) = d.part1(&text) else {
+                panic!("Expected a numeric answer.");
+            };
+            assert!(p1 <= 20);
+            assert!(matches!(d.part2(&text), Answer::Numeric(n) if n > 0));
+        }
+    }
+
+    #[test]
+    // Compare both parts against the set of covered ids, on small random inputs.
+    fn test_oracle() {
+        let d = Day5::new();
+        let mut rng = Rng::new(5);
+        for _ in 0..300 {
+            let mut text = String::new();
+            let mut covered = HashSet::new();
+            for _ in 0..rng.range(1, 8) {
+                let low = rng.range(1, 100);
+                let high = low + rng.range(0, 30);
+                covered.extend(low..=high);
+                text.push_str(&format!("{low}-{high}\n"));
+            }
+            text.push('\n');
+            let ingredients: Vec<usize> = (0..rng.range(0, 10)).map(|_| { rng.range(1, 140) }).collect();
+            for i in &ingredients {
+                text.push_str(&format!("{i}\n"));
+            }
+
+            let fresh = ingredients.iter().filter(|i| { covered.contains(i) }).count();
+            assert_eq!(d.part1(&text), Answer::Numeric(fresh), "input:\n{text}");
+            assert_eq!(d.part2(&text), Answer::Numeric(covered.len()), "input:\n{text}");
+        }
+    }
+
+    #[test]
+    // Streaming the ingredients gives the same answers.
+    fn test_stream() {
+        let d = Day5::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            assert_eq!(d.solve_stream(1, &mut text.as_bytes()), Some(d.try_part1(&text)));
+            assert_eq!(d.solve_stream(2, &mut text.as_bytes()), Some(d.try_part2(&text)));
+        }
+        assert_eq!(d.solve_stream(1, &mut EXAMPLE1.as_bytes()), Some(Ok(Answer::Numeric(3))));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day5::new(), &[EXAMPLE1]);
+    }
 }
@@ -1,12 +1,36 @@
-use crate::day::{Day, Answer};
+use std::cmp::max;
+
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::trace;
 use regex::Regex;
 
+const DAY: usize = 6;
+
 #[derive(PartialEq, Debug)]
 enum Operation {
     ADD,
     MUL,
 }
 
+impl Operation {
+    fn apply<T: Arith>(&self, mut operands: impl Iterator<Item = T>) -> Result<T, SolveError> {
+        match self {
+            Operation::ADD => {
+                operands.try_fold(T::from_usize(0), |acc, v| { acc.checked_add(&v) })
+                    .ok_or(SolveError::overflow(DAY, "sum of operands"))
+            }
+            Operation::MUL => {
+                operands.try_fold(T::from_usize(1), |acc, v| { acc.checked_mul(&v) })
+                    .ok_or(SolveError::overflow(DAY, "product of operands"))
+            }
+        }
+    }
+}
+
 // A representation of the puzzle inputs.
 struct Input {
     operands: Vec<Vec<usize>>,
@@ -17,7 +41,7 @@ struct Input {
 
 impl Input {
     
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let operand_re: Regex = Regex::new("([0-9]+)").unwrap();
         let operation_re: Regex = Regex::new("([\\*\\+])").unwrap();
@@ -26,7 +50,7 @@ impl Input {
         let mut op_cols = Vec::new();
         let mut operand_text = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
 
             if operand_re.is_match(line) {
@@ -35,7 +59,8 @@ impl Input {
 
                 let mut line_operands = Vec::new();
                 for operand in operand_re.find_iter(line) {
-                    let n = operand.as_str().parse::<usize>().unwrap();
+                    let n = operand.as_str().parse::<usize>()
+                        .map_err(|_| { SolveError::parse(line_no + 1, format!("Operand too big: {}", operand.as_str())) })?;
                     line_operands.push(n);
                 }
                 operands.push(line_operands);
@@ -43,43 +68,85 @@ impl Input {
             }
             else if operation_re.is_match(line) {
                 for operation in operation_re.find_iter(line) {
-                    let col = operation.start();
+                    // Column in chars, to match operand_text.
+                    let col = line[..operation.start()].chars().count();
                     op_cols.push(col);
 
                     let op = match operation.as_str() {
                         "+" => Operation::ADD,
-                        "*" => Operation::MUL,
-                        _ => { panic!("Bad operation in input.")}
+                        _ => Operation::MUL,
                     };
                     operations.push(op);
                 }
             }
             else {
-                panic!("Couldn't parse input line.");
+                return Err(SolveError::parse(line_no + 1, "Couldn't parse input line."));
+            }
+        }
+
+        // Every row of operands needs one operand per operation.
+        if operands.is_empty() && !operations.is_empty() {
+            return Err(SolveError::parse(1, "No operands."));
+        }
+        for (line_no, row) in operands.iter().enumerate() {
+            if row.len() != operations.len() {
+                return Err(SolveError::parse(line_no + 1,
+                    format!("{} operands for {} operations.", row.len(), operations.len())));
             }
         }
 
-        Input { operands, operations, op_cols, operand_text }
+        Ok(Input { operands, operations, op_cols, operand_text })
     }
 
-    fn eval(&self, n: usize) -> usize {
-        let value = match self.operations[n] {
-            Operation::ADD => {
-                self.operands.iter()
-                    .map(|row| { row[n] })
-                    .sum()
+    // Random input in the format read() accepts: `size` problems of 2 to 4 operands each.
+    //
+    // Operands have 1 to 4 digits, never zero, since eval2() treats a column with no digits as
+    // the end of a problem.  Within a problem the operands are all left or all right aligned.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let n_rows = rng.range(2, 4);
+        let mut rows = vec![String::new(); n_rows];
+        let mut ops = String::new();
+
+        for problem in 0..max(size, 1) {
+            if problem > 0 {
+                // Blank column between problems
+                rows.iter_mut().for_each(|row| { row.push(' ') });
+                ops.push(' ');
             }
-            Operation::MUL => {
-                self.operands.iter()
-                    .map(|row| { row[n] })
-                    .product()
+
+            let operands: Vec<String> = (0..n_rows)
+                .map(|_| {
+                    (0..rng.range(1, 4)).map(|_| { char::from(b'0' + rng.range(1, 9) as u8) }).collect()
+                })
+                .collect();
+            let width = operands.iter().map(|o| { o.len() }).max().unwrap();
+            let left_aligned = rng.chance(1, 2);
+            for (row, operand) in rows.iter_mut().zip(operands) {
+                if left_aligned {
+                    row.push_str(&format!("{operand:<width$}"));
+                }
+                else {
+                    row.push_str(&format!("{operand:>width$}"));
+                }
             }
-        };
 
-        value
+            let op = if rng.chance(1, 2) { "+" } else { "*" };
+            ops.push_str(&format!("{op:<width$}"));
+        }
+
+        let mut text = rows.join("\n");
+        text.push('\n');
+        text.push_str(&ops);
+        text.push('\n');
+
+        text
     }
 
-    fn eval2(&self, n: usize) -> usize {
+    fn eval<T: Arith>(&self, n: usize) -> Result<T, SolveError> {
+        self.operations[n].apply(self.operands.iter().map(|row| { T::from_usize(row[n]) }))
+    }
+
+    fn eval2<T: Arith>(&self, n: usize) -> Result<T, SolveError> {
         // TODO: Find column number for operation n
         // form operands, in weird ordering.
         let mut v_operands = Vec::new();
@@ -87,15 +154,17 @@ impl Input {
         let mut col = self.op_cols[n];
         while col < self.operand_text[0].len() {
             // Form number for this col
-            let mut value = 0_usize;
+            let mut value = T::from_usize(0);
             for row in 0..self.operands.len() {
-                let c = self.operand_text[row][col];
-                if c.is_digit(10) {
-                    value *= 10;
-                    value += self.operand_text[row][col].to_digit(10).unwrap() as usize;
+                // (Short rows are treated as ending in spaces.)
+                let c = self.operand_text[row].get(col).copied().unwrap_or(' ');
+                if let Some(digit) = c.to_digit(10) {
+                    value = value.checked_mul(&T::from_usize(10))
+                        .and_then(|v| { v.checked_add(&T::from_usize(digit as usize)) })
+                        .ok_or(SolveError::overflow(DAY, "column operand"))?;
                 }
             }
-            if value == 0 {
+            if value == T::from_usize(0) {
                 break;
             }
             else {
@@ -105,20 +174,10 @@ impl Input {
             col += 1;
         }
 
-        let value = match self.operations[n] {
-            Operation::ADD => {
-                v_operands.iter()
-                    .sum()
-            }
-            Operation::MUL => {
-                v_operands.iter()
-                    .product()
-            }
-        };
-
-        // println!("    result: {value}");
+        let result = self.operations[n].apply(v_operands.into_iter());
+        trace::note(|| { format!("Problem {n}: {result:?}") });
 
-        value
+        result
     }
 }
 
@@ -129,41 +188,54 @@ impl Day6 {
     pub const fn new() -> Self {
         Self { }
     }
+
+    // Sum of the results of every problem, each evaluated with `eval`.
+    fn sum_results<T: Arith>(input: &Input, eval: fn(&Input, usize) -> Result<T, SolveError>) -> Result<Answer, SolveError> {
+        let mut sum = T::from_usize(0);
+        for n in 0..input.operations.len() {
+            sum = sum.checked_add(&eval(input, n)?).ok_or(SolveError::overflow(DAY, "sum of results"))?;
+        }
+
+        Ok(sum.into_answer())
+    }
 }
 
 impl Day for Day6 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
-
-        let mut sum = 0;
-        for n in 0..input.operations.len() {
-            sum += input.eval(n);
-        }
+        let input = Input::read(text)?;
 
-        Answer::Numeric(sum)
+        arith::widen(|| { Day6::sum_results::<usize>(&input, Input::eval) },
+                     || { Day6::sum_results::<u128>(&input, Input::eval) },
+                     || { Day6::sum_results::<BigUint>(&input, Input::eval) })
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
-        let mut sum = 0;
-        for n in 0..input.operations.len() {
-            sum += input.eval2(n);
-        }
+        arith::widen(|| { Day6::sum_results::<usize>(&input, Input::eval2) },
+                     || { Day6::sum_results::<u128>(&input, Input::eval2) },
+                     || { Day6::sum_results::<BigUint>(&input, Input::eval2) })
+    }
 
-        Answer::Numeric(sum)
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
     }
 }
 
 #[cfg(test)]
 mod test {
+    use num_bigint::BigUint;
+
+    use crate::arith::{set_policy, Policy};
     use crate::day6::{Day6, Input, Operation};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
     
     const EXAMPLE1: &str = "\
 123 328  51 64 
@@ -175,7 +247,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.operands.len(), 3);
         assert_eq!(input.operands[0].len(), 4);
@@ -191,22 +263,22 @@ mod test {
 
     #[test]
     fn test_eval() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.eval(0), 33210);
-        assert_eq!(input.eval(1), 490);
-        assert_eq!(input.eval(2), 4243455);
-        assert_eq!(input.eval(3), 401);
+        assert_eq!(input.eval::<usize>(0), Ok(33210));
+        assert_eq!(input.eval::<usize>(1), Ok(490));
+        assert_eq!(input.eval::<usize>(2), Ok(4243455));
+        assert_eq!(input.eval::<usize>(3), Ok(401));
     }
 
     #[test]
     fn test_eval2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.eval2(3), 1058);
-        assert_eq!(input.eval2(2), 3253600);
-        assert_eq!(input.eval2(1), 625);
-        assert_eq!(input.eval2(0), 8544);
+        assert_eq!(input.eval2::<usize>(3), Ok(1058));
+        assert_eq!(input.eval2::<usize>(2), Ok(3253600));
+        assert_eq!(input.eval2::<usize>(1), Ok(625));
+        assert_eq!(input.eval2::<usize>(0), Ok(8544));
     }
 
     #[test]
@@ -225,5 +297,46 @@ mod test {
         let d = Day6::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(3263827));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.
+    fn test_generate() {
+        let d = Day6::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!(input.operations.len(), 20);
+            assert!(input.operands.iter().all(|row| { row.len() == 20 }));
+
+            assert!(matches!(d.part1(&text), Answer::Numeric(n) if n > 0));
+            assert!(matches!(d.part2(&text), Answer::Numeric(n) if n > 0));
+        }
+    }
+
+    #[test]
+    // Products too big for a usize, or a u128, are an error unless the policy widens them.
+    fn test_overflow() {
+        let d = Day6::new();
+        let nines = BigUint::from(9_999_999_999_usize);
+        let three_rows = "9999999999\n9999999999\n9999999999\n*\n";
+        let four_rows = "9999999999\n9999999999\n9999999999\n9999999999\n*\n";
+
+        set_policy(Policy::Checked);
+        assert_eq!(d.try_part1(three_rows), Err(SolveError::overflow(6, "product of operands")));
+
+        set_policy(Policy::Wide);
+        assert_eq!(d.try_part1(three_rows), Ok(Answer::Big(nines.pow(3))));
+        assert_eq!(d.try_part1(four_rows), Err(SolveError::overflow(6, "product of operands")));
+
+        set_policy(Policy::Big);
+        assert_eq!(d.try_part1(four_rows), Ok(Answer::Big(nines.pow(4))));
+
+        set_policy(Policy::Checked);
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day6::new(), &[EXAMPLE1]);
+    }
 }
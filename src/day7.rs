@@ -1,6 +1,13 @@
-use std::{collections::HashSet, mem};
+use std::{cmp::{max, min}, collections::HashSet, mem};
 
-use crate::day::{Day, Answer};
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::vis::{Style, Svg};
+
+const DAY: usize = 7;
 
 // A representation of the puzzle inputs.
 struct Input {
@@ -8,23 +15,98 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
-        let mut rows = Vec::new();
-        for line in text.lines() {
+        let mut rows: Vec<Vec<char>> = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
             let mut row = Vec::new();
             for c in line.chars() {
                 row.push(c);
             }
+
+            let width = rows.first().map_or(row.len(), |first: &Vec<char>| { first.len() });
+            if let Some(problem) = Input::row_problems(line_no + 1, &row, width).into_iter().next() {
+                return Err(problem);
+            }
             rows.push(row);
         }
 
-        Input { rows }
+        Ok(Input { rows })
+    }
+
+    // What's wrong with a row, if anything.  Every row is `width` wide, the width of the first,
+    // and splitters need room on both sides.
+    fn row_problems(line_no: usize, row: &[char], width: usize) -> Vec<SolveError> {
+        let mut problems = Vec::new();
+
+        if row.len() != width {
+            problems.push(SolveError::parse(line_no, format!("Row is {} wide, expected {width}.", row.len())));
+        }
+        for (col_no, c) in row.iter().enumerate() {
+            match c {
+                'S' | '.' => {}
+                '^' if col_no > 0 && col_no + 1 < row.len() => {}
+                '^' => problems.push(SolveError::parse(line_no, format!("Splitter on the edge, col:{col_no}"))),
+                _ => problems.push(SolveError::parse(line_no, format!("Unexpected character '{c}', col:{col_no}"))),
+            }
+        }
+
+        problems
+    }
+
+    // Every problem with every row.
+    fn lint(text: &str) -> Vec<SolveError> {
+        let width = text.lines().next().map_or(0, |line| { line.chars().count() });
+
+        text.lines().enumerate()
+            .flat_map(|(line_no, line)| {
+                Input::row_problems(line_no + 1, &line.chars().collect::<Vec<char>>(), width)
+            })
+            .collect()
+    }
+
+    // Random input in the format read() accepts: a manifold `2 * size + 1` columns wide with
+    // the start in the middle and up to 60 rows of splitters (so the timeline count fits in a
+    // usize.)  Splitters never sit on the edge columns or next to each other.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = max(size, 1);
+        let width = 2 * size + 1;
+        let mut text = String::new();
+
+        let mut row = vec!['.'; width];
+        row[size] = 'S';
+        text.extend(row.iter());
+        text.push('\n');
+
+        for _ in 0..min(size, 60) {
+            let blank = ".".repeat(width);
+            text.push_str(&blank);
+            text.push('\n');
+
+            let mut row = vec!['.'; width];
+            for col in (1..width-1).filter(|col| { col % 2 == size % 2 }) {
+                if rng.chance(1, 2) {
+                    row[col] = '^';
+                }
+            }
+            text.extend(row.iter());
+            text.push('\n');
+        }
+        text.push_str(&".".repeat(width));
+        text.push('\n');
+
+        text
     }
 
     fn splits(&self) -> usize {
+        self.beams().0
+    }
+
+    // The number of times a beam is split, and the columns with a beam leaving each row.
+    fn beams(&self) -> (usize, Vec<HashSet<usize>>) {
         let mut beams: HashSet<usize> = HashSet::new();
         let mut splits = 0;
+        let mut leaving = Vec::new();
 
         for row in &self.rows {
             for (n, c) in row.iter().enumerate() {
@@ -39,23 +121,79 @@ impl Input {
                     beams.insert(n+1);
                 }
             }
+            leaving.push(beams.clone());
         }
 
-        splits
+        (splits, leaving)
     }
 
-    fn timelines(&self) -> usize {
+    // The manifold with beams drawn through the first `upto` rows.  Splitters a beam has hit
+    // are marked.
+    fn draw(&self, style: Style, beams: &[HashSet<usize>], upto: usize) -> String {
+        const CELL: f64 = 6.0;
+
+        // A splitter is hit if a beam comes down onto it from the row above.
+        let hit = |row_no: usize, col_no: usize| {
+            row_no > 0 && row_no <= upto && beams[row_no-1].contains(&col_no)
+        };
+        match style {
+            Style::Ascii => {
+                let mut text = String::new();
+                for (row_no, row) in self.rows.iter().enumerate() {
+                    for (col_no, c) in row.iter().enumerate() {
+                        text.push(match c {
+                            '.' if row_no < upto && beams[row_no].contains(&col_no) => '|',
+                            '^' if hit(row_no, col_no) => '*',
+                            c => *c,
+                        });
+                    }
+                    text.push('\n');
+                }
+
+                text
+            }
+            Style::Svg => {
+                let width = self.rows.first().map_or(0, |row| { row.len() });
+                let mut svg = Svg::new(width as f64 * CELL, self.rows.len() as f64 * CELL);
+                svg.rect(0.0, 0.0, width as f64 * CELL, self.rows.len() as f64 * CELL, "#1b1b2f");
+                for (row_no, row) in self.rows.iter().enumerate() {
+                    let y = row_no as f64 * CELL;
+                    for (col_no, c) in row.iter().enumerate() {
+                        let x = col_no as f64 * CELL;
+                        match c {
+                            'S' => svg.rect(x, y, CELL, CELL, "#59a14f"),
+                            '^' if hit(row_no, col_no) => svg.rect(x, y, CELL, CELL, "#e15759"),
+                            '^' => svg.rect(x, y, CELL, CELL, "#888888"),
+                            _ if row_no < upto && beams[row_no].contains(&col_no) => {
+                                svg.rect(x + CELL / 3.0, y, CELL / 3.0, CELL, "#edc948");
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                svg.finish()
+            }
+        }
+    }
+
+    fn timelines<T: Arith>(&self) -> Result<T, SolveError> {
+        let width = self.rows.first().map_or(0, |row| { row.len() });
+        let zero = T::from_usize(0);
+
         // Ping pong buffers for prior_timelines and curr_timelines
-        let mut timelines_a = vec![0; self.rows[0].len()];
-        let mut timelines_b = vec![0; self.rows[0].len()];
+        let mut timelines_a: Vec<T> = vec![zero.clone(); width];
+        let mut timelines_b: Vec<T> = vec![zero.clone(); width];
 
         let prior_timelines = &mut timelines_a;
         let curr_timelines = &mut timelines_b;
 
+        let overflow = || { SolveError::overflow(DAY, "timeline count") };
+
         for row in &self.rows {
             // Clear old values
             for col_no in 0..row.len() {
-                curr_timelines[col_no] = 0;
+                curr_timelines[col_no] = zero.clone();
             }
 
             // Accumulate timelines
@@ -64,19 +202,16 @@ impl Input {
                 match row[col_no] {
                     'S' => {
                         // Start a tachyon beam with 1 timeline
-                        curr_timelines[col_no] += 1;
-                    }
-                    '.' => {
-                        // Timelines can flow straight down
-                        curr_timelines[col_no] += prior_timelines[col_no];
+                        curr_timelines[col_no] = curr_timelines[col_no].checked_add(&T::from_usize(1)).ok_or_else(overflow)?;
                     }
                     '^' => {
                         // A splitter here duplicates timelines
-                        curr_timelines[col_no-1] += prior_timelines[col_no];
-                        curr_timelines[col_no+1] += prior_timelines[col_no];
+                        curr_timelines[col_no-1] = curr_timelines[col_no-1].checked_add(&prior_timelines[col_no]).ok_or_else(overflow)?;
+                        curr_timelines[col_no+1] = curr_timelines[col_no+1].checked_add(&prior_timelines[col_no]).ok_or_else(overflow)?;
                     }
                     _ => {
-                        panic!("Unexpected character in input array: '{}', col:{col_no}", row[col_no]);
+                        // Timelines can flow straight down
+                        curr_timelines[col_no] = curr_timelines[col_no].checked_add(&prior_timelines[col_no]).ok_or_else(overflow)?;
                     }
                 }
             }
@@ -84,7 +219,7 @@ impl Input {
             mem::swap(prior_timelines, curr_timelines);
         }
 
-        prior_timelines.iter().sum()
+        prior_timelines.iter().try_fold(zero, |acc, n| { acc.checked_add(n) }).ok_or_else(overflow)
     }
 }
 
@@ -100,26 +235,50 @@ impl Day7 {
 impl Day for Day7 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
-        Answer::Numeric(input.splits())
+        Ok(Answer::Numeric(input.splits()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
+
+        arith::widen(|| { Ok(input.timelines::<usize>()?.into_answer()) },
+                     || { Ok(input.timelines::<u128>()?.into_answer()) },
+                     || { Ok(input.timelines::<BigUint>()?.into_answer()) })
+    }
 
-        Answer::Numeric(input.timelines())
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        Some(Input::lint(text))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // The beams going down the manifold, a row per frame.
+    fn visualize(&self, text: &str, style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        let input = match Input::read(text) {
+            Ok(input) => input,
+            Err(e) => return Some(Err(e)),
+        };
+        let (_, beams) = input.beams();
+
+        Some(Ok((1..=input.rows.len()).map(|upto| { input.draw(style, &beams, upto) }).collect()))
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day7::{Day7, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
+    use crate::vis::Style;
     
     const EXAMPLE1: &str = "\
 .......S.......
@@ -143,7 +302,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.rows.len(), 16);
         assert_eq!(input.rows[0].len(), 15);
@@ -154,16 +313,31 @@ mod test {
 
     #[test]
     fn test_splits() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.splits(), 21);
     }
 
+    #[test]
+    // The last frame has the beams all the way down, and every split marked.
+    fn test_visualize() {
+        let d = Day7::new();
+        let frames = d.visualize(EXAMPLE1, Style::Ascii).unwrap().unwrap();
+        assert_eq!(frames.len(), 16);
+        assert_eq!(frames[0], EXAMPLE1);
+        assert!(frames[2].starts_with(".......S.......\n.......|.......\n......|*|......\n...............\n"));
+
+        let last = frames.last().unwrap();
+        assert_eq!(last.matches('*').count(), 21);
+        assert!(last.ends_with("|.|.|.|.|.|||.|\n"));
+        assert!(d.visualize("..S\n.x.\n", Style::Svg).unwrap().is_err());
+    }
+
     #[test]
     fn test_timelines() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.timelines(), 40);
+        assert_eq!(input.timelines::<usize>(), Ok(40));
     }
 
     #[test]
@@ -182,5 +356,41 @@ mod test {
         let d = Day7::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(40));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.  Each split adds at least one timeline.
+    fn test_generate() {
+        let d = Day7::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert!(input.rows.iter().all(|row| { row.len() == 41 }));
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p2 > p1);
+        }
+    }
+
+    #[test]
+    // Lint reports every bad row, not just the first.
+    fn test_lint() {
+        let d = Day7::new();
+        assert_eq!(d.lint(EXAMPLE1), Some(Vec::new()));
+
+        let bad = "..S..\n^....\n.....\n..^.\n..x.^\n";
+        assert_eq!(d.lint(bad), Some(vec![
+            SolveError::parse(2, "Splitter on the edge, col:0"),
+            SolveError::parse(4, "Row is 4 wide, expected 5."),
+            SolveError::parse(5, "Unexpected character 'x', col:2"),
+            SolveError::parse(5, "Splitter on the edge, col:4"),
+        ]));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day7::new(), &[EXAMPLE1]);
+    }
 }
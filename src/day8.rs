@@ -1,78 +1,104 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{cmp::{max, Ordering}, collections::HashSet};
 
-use crate::day::{Day, Answer};
+use num_bigint::BigUint;
+
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError};
+use crate::rng::Rng;
+use crate::vis::{scale, Canvas, Style, Svg, PALETTE};
 use vector3d::Vector3d;
 use regex::Regex;
 
+const DAY: usize = 8;
+
 // A representation of the puzzle inputs.
 struct Input {
     coords: Vec<Vector3d<usize>>,
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
         let vector_re = Regex::new("([0-9]+),([0-9]+),([0-9]+)").unwrap();
         let mut coords = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
-            if let Some(m) = vector_re.captures(line) {
-                let x = m[1].parse::<usize>().unwrap();
-                let y = m[2].parse::<usize>().unwrap();
-                let z = m[3].parse::<usize>().unwrap();
+            let m = vector_re.captures(line)
+                .ok_or_else(|| { SolveError::parse(line_no + 1, format!("Expected x,y,z: '{line}'")) })?;
+            let coord = |n: usize| {
+                m[n].parse::<usize>()
+                    .map_err(|_| { SolveError::parse(line_no + 1, format!("Coordinate too big: {}", &m[n])) })
+            };
+
+            coords.push(Vector3d::new(coord(1)?, coord(2)?, coord(3)?));
+        }
 
-                coords.push(Vector3d::new(x, y, z));
-            }
+        Ok(Input { coords })
+    }
+
+    // Random input in the format read() accepts: `size` junction boxes with coordinates below
+    // 100000.  Part 1 connects the 1000 closest pairs, so full-size inputs need around 1000 boxes.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut text = String::new();
+        for _ in 0..size {
+            text.push_str(&format!("{},{},{}\n", rng.below(100_000), rng.below(100_000), rng.below(100_000)));
         }
 
-        Input { coords }
+        text
     }
 
     // returns (index1, index2) in vector shorted from smallest distance to largest.
-    fn pairs_by_dist(&self) -> Vec<(usize, usize)> {
+    fn pairs_by_dist(&self) -> Result<Vec<(usize, usize)>, SolveError> {
+        arith::widen(|| { self.pairs_by_dist_in::<usize>() },
+                     || { self.pairs_by_dist_in::<u128>() },
+                     || { self.pairs_by_dist_in::<BigUint>() })
+    }
+
+    // pairs_by_dist(), working out squared distances in T.
+    fn pairs_by_dist_in<T: Arith>(&self) -> Result<Vec<(usize, usize)>, SolveError> {
         let mut pairs = Vec::new();
-        for n in 0..self.coords.len()-1 {
+        for n in 0..self.coords.len() {
             for m in n+1..self.coords.len() {
-                pairs.push((n, m));
+                let a = self.coords[n];
+                let b = self.coords[m];
+
+                let dx = T::from_usize(a.x.abs_diff(b.x));
+                let dy = T::from_usize(a.y.abs_diff(b.y));
+                let dz = T::from_usize(a.z.abs_diff(b.z));
+                let dist_squared = dx.checked_mul(&dx)
+                    .zip(dy.checked_mul(&dy))
+                    .zip(dz.checked_mul(&dz))
+                    .and_then(|((x2, y2), z2)| { x2.checked_add(&y2)?.checked_add(&z2) })
+                    .ok_or(SolveError::overflow(DAY, "squared distance"))?;
+
+                pairs.push((dist_squared, (n, m)));
             }
         }
 
-        pairs.sort_by(|pair_a, pair_b| {
-            let a1 = self.coords[pair_a.0];
-            let a2 = self.coords[pair_a.1];
-            let b1 = self.coords[pair_b.0];
-            let b2 = self.coords[pair_b.1];
+        // (A stable sort, so equal distances keep their input order.)
+        pairs.sort_by(|(a, _), (b, _)| { a.cmp(b) });
 
-            let dx = a1.x.abs_diff(a2.x);
-            let dy = a1.y.abs_diff(a2.y);
-            let dz = a1.z.abs_diff(a2.z);
-            let dist_a_squared = dx*dx + dy*dy + dz*dz;
-
-            let dx = b1.x.abs_diff(b2.x);
-            let dy = b1.y.abs_diff(b2.y);
-            let dz = b1.z.abs_diff(b2.z);
-            let dist_b_squared = dx*dx + dy*dy + dz*dz;
-
-            if dist_a_squared < dist_b_squared {
-                Ordering::Less
-            }
-            else if dist_a_squared > dist_b_squared {
-                Ordering::Greater
-            }
-            else {
-                Ordering::Equal
-            }
+        Ok(pairs.into_iter().map(|(_, pair)| { pair }).collect())
+    }
 
-        });
+    fn make_networks(&self, n_connections: usize) -> Result<Vec<HashSet<usize>>, SolveError> {
+        if self.coords.len() < n_connections {
+            return Err(SolveError::Unsolvable(format!("Only {} junction boxes, fewer than the {n_connections} connections to make.",
+                                                      self.coords.len())));
+        }
+        let pairs = self.pairs_by_dist()?;
+        if pairs.len() < n_connections {
+            return Err(SolveError::Unsolvable(format!("Only {} pairs to connect, need {n_connections}.", pairs.len())));
+        }
 
-        pairs
+        Ok(Input::join(&pairs[..n_connections]))
     }
 
-    fn make_networks(&self, n_connections: usize) -> Vec<HashSet<usize>> {
+    // The networks made by connecting each of the pairs, biggest first.
+    fn join(pairs: &[(usize, usize)]) -> Vec<HashSet<usize>> {
         let mut networks: Vec<HashSet<usize>> = Vec::new();
-        let pairs = self.pairs_by_dist();
-        for n in 0..n_connections {
+        for n in 0..pairs.len() {
             // Connect pairs[n], adding the resulting network to our Vec<HashSet<>> of
             // networks.
             // If neither element of the pair is in a network, make a new HashSet and
@@ -156,9 +182,74 @@ impl Input {
         networks
     }
 
+    // The boxes seen from above (x and y, ignoring z) with `pairs` connected.  The three
+    // biggest networks are picked out: as A, B and C in ASCII, in colors in SVG.
+    fn draw(&self, style: Style, pairs: &[(usize, usize)]) -> String {
+        const WIDTH: usize = 72;
+        const HEIGHT: usize = 36;
+        const SIZE: f64 = 600.0;
+
+        let networks = Input::join(pairs);
+        let mut rank = vec![None; self.coords.len()];
+        for (n, net) in networks.iter().enumerate() {
+            for b in net {
+                rank[*b] = Some(n);
+            }
+        }
+        let sizes: Vec<String> = networks.iter().take(3).map(|net| { net.len().to_string() }).collect();
+        let caption = format!("{} connections, biggest networks: {}", pairs.len(), sizes.join(", "));
+
+        let min_x = self.coords.iter().map(|c| { c.x }).min().unwrap_or(0);
+        let max_x = self.coords.iter().map(|c| { c.x }).max().unwrap_or(0);
+        let min_y = self.coords.iter().map(|c| { c.y }).min().unwrap_or(0);
+        let max_y = self.coords.iter().map(|c| { c.y }).max().unwrap_or(0);
+
+        // Draw lone boxes first, then small networks, so the big ones end up on top.
+        let mut order: Vec<usize> = (0..self.coords.len()).collect();
+        order.sort_by_key(|b| { std::cmp::Reverse(rank[*b].map_or(usize::MAX, |r| { r })) });
+
+        match style {
+            Style::Ascii => {
+                let mut canvas = Canvas::new(WIDTH, HEIGHT);
+                for b in order {
+                    let c = match rank[b] {
+                        Some(r) if r < 3 => (b'A' + r as u8) as char,
+                        Some(_) => '+',
+                        None => 'o',
+                    };
+                    canvas.set(scale(self.coords[b].x, min_x, max_x, WIDTH), scale(self.coords[b].y, min_y, max_y, HEIGHT), c);
+                }
+
+                format!("{caption}\n{}", canvas.render())
+            }
+            Style::Svg => {
+                let k = SIZE / (max(max_x - min_x, max_y - min_y).max(1)) as f64;
+                let at = |b: usize| {
+                    ((self.coords[b].x - min_x) as f64 * k + 10.0, (self.coords[b].y - min_y) as f64 * k + 30.0)
+                };
+                let mut svg = Svg::new((max_x - min_x) as f64 * k + 20.0, (max_y - min_y) as f64 * k + 40.0);
+                svg.text(10.0, 18.0, 14.0, &caption);
+                for (b0, b1) in pairs {
+                    svg.line(at(*b0), at(*b1), "#bbbbbb", 1.0);
+                }
+                for b in order {
+                    let fill = match rank[b] {
+                        Some(r) if r < 3 => PALETTE[r],
+                        Some(_) => "#888888",
+                        None => "#dddddd",
+                    };
+                    let (x, y) = at(b);
+                    svg.circle(x, y, 3.0, fill);
+                }
+
+                svg.finish()
+            }
+        }
+    }
+
     // Keep combining boxes until all are in one network.
     // Return the Node ids of the last two combined.
-    fn make_one_network(&self) -> Option<(usize, usize)> {
+    fn make_one_network(&self) -> Result<Option<(usize, usize)>, SolveError> {
         let mut networks: Vec<HashSet<usize>> = Vec::new();
 
         // Put each junction box into its own network of one node
@@ -168,7 +259,7 @@ impl Input {
             networks.push(singular_net);
         }
 
-        let pairs = self.pairs_by_dist();
+        let pairs = self.pairs_by_dist()?;
         
         for n in 0..pairs.len()  {
             // Connect pairs[n], adding the resulting network to our Vec<HashSet<>> of
@@ -239,11 +330,11 @@ impl Input {
 
             // If we are down to one network, we're done
             if networks.len() == 1 {
-                return Some(pairs[n]);
+                return Ok(Some(pairs[n]));
             }
         }
 
-        None
+        Ok(None)
     }
 }
 
@@ -256,6 +347,13 @@ impl Day8 {
         Self { to_connect: 1000 }
     }
 
+    fn x_product<T: Arith>(x0: usize, x1: usize) -> Result<Answer, SolveError> {
+        let product = T::from_usize(x0).checked_mul(&T::from_usize(x1))
+            .ok_or(SolveError::overflow(DAY, "product of x coordinates"))?;
+
+        Ok(product.into_answer())
+    }
+
     #[cfg(test)]
     fn to_connect(&mut self, n: usize) {
         self.to_connect = n;
@@ -265,37 +363,67 @@ impl Day8 {
 impl Day for Day8 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
 
-        let input = Input::read(text);   
-        let nets = input.make_networks(self.to_connect);
+        let input = Input::read(text)?;
+        let nets = input.make_networks(self.to_connect)?;
+        if nets.len() < 3 {
+            return Err(SolveError::Unsolvable(format!("Only {} networks, need 3.", nets.len())));
+        }
 
-        let result = nets[0].len() * nets[1].len() * nets[2].len();
+        let result = nets[0].len().checked_mul(nets[1].len())
+            .and_then(|p| { p.checked_mul(nets[2].len()) })
+            .ok_or(SolveError::overflow(DAY, "product of network sizes"))?;
 
-        Answer::Numeric(result)
+        Ok(Answer::Numeric(result))
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);   
+        let input = Input::read(text)?;
 
-        if let Some(nodes) = input.make_one_network() {
-            Answer::Numeric(input.coords[nodes.0].x * input.coords[nodes.1].x)
+        if let Some(nodes) = input.make_one_network()? {
+            let (x0, x1) = (input.coords[nodes.0].x, input.coords[nodes.1].x);
+            arith::widen(|| { Day8::x_product::<usize>(x0, x1) },
+                         || { Day8::x_product::<u128>(x0, x1) },
+                         || { Day8::x_product::<BigUint>(x0, x1) })
         }
         else {
-            Answer::None
+            Ok(Answer::None)
         }
 
 
     }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // Part 1's networks forming, a tenth of the connections at a time.
+    fn visualize(&self, text: &str, style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        const STEPS: usize = 10;
+
+        let frames = Input::read(text).and_then(|input| {
+            let pairs = input.pairs_by_dist()?;
+            if pairs.len() < self.to_connect {
+                return Err(SolveError::Unsolvable(format!("Only {} pairs to connect, need {}.", pairs.len(), self.to_connect)));
+            }
+            Ok((0..=STEPS).map(|step| { input.draw(style, &pairs[..self.to_connect * step / STEPS]) }).collect())
+        });
+
+        Some(frames)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day8::{Day8, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError};
+    use crate::rng::Rng;
+    use crate::fuzz;
+    use crate::vis::Style;
     
     const EXAMPLE1: &str = "\
 162,817,812
@@ -323,7 +451,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.coords.len(), 20);
         assert_eq!(input.coords[0].x, 162);
@@ -333,9 +461,9 @@ mod test {
 
     #[test]
     fn test_ordering() {
-        let input = Input::read(EXAMPLE1);   
+        let input = Input::read(EXAMPLE1).unwrap();   
 
-        let pairs = input.pairs_by_dist();
+        let pairs = input.pairs_by_dist().unwrap();
         assert_eq!(pairs.len(), 20*19/2);
         assert_eq!(pairs[0].0, 0);
         assert_eq!(pairs[0].1, 19);
@@ -343,9 +471,9 @@ mod test {
 
     #[test]
     fn test_make_networks() {
-        let input = Input::read(EXAMPLE1);   
+        let input = Input::read(EXAMPLE1).unwrap();   
 
-        let nets = input.make_networks(10);
+        let nets = input.make_networks(10).unwrap();
         assert_eq!(nets.len(), 4);
         assert_eq!(nets[0].len(), 5);
         assert_eq!(nets[1].len(), 4);
@@ -355,10 +483,30 @@ mod test {
 
     #[test]
     fn test_make_one_network() {
-        let input = Input::read(EXAMPLE1);   
+        let input = Input::read(EXAMPLE1).unwrap();   
 
         let nodes = input.make_one_network();
-        assert_eq!(nodes, Some((10, 12)));
+        assert_eq!(nodes, Ok(Some((10, 12))));
+    }
+
+    #[test]
+    // Frames go from no connections to part 1's networks.
+    fn test_visualize() {
+        let mut d = Day8::new();
+        d.to_connect(10);
+        let frames = d.visualize(EXAMPLE1, Style::Ascii).unwrap().unwrap();
+        assert_eq!(frames.len(), 11);
+        assert!(frames[0].starts_with("0 connections, biggest networks: \n"));
+        let (_, boxes) = frames[0].split_once('\n').unwrap();
+        assert!(boxes.contains('o') && !boxes.contains('A'));
+        assert!(frames[10].starts_with("10 connections, biggest networks: 5, 4, 2\n"));
+
+        let svg = d.visualize(EXAMPLE1, Style::Svg).unwrap().unwrap();
+        assert_eq!(svg[10].matches("<line ").count(), 10);
+        assert_eq!(svg[10].matches("<circle ").count(), 20);
+
+        d.to_connect(1000);
+        assert!(d.visualize(EXAMPLE1, Style::Ascii).unwrap().is_err());
     }
 
     #[test]
@@ -379,5 +527,31 @@ mod test {
         let d = Day8::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(25272));
     }
-    
+
+    #[test]
+    // Solve randomly generated inputs.
+    fn test_generate() {
+        let mut d = Day8::new();
+        d.to_connect(10);
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 50).unwrap();
+            assert_eq!(Input::read(&text).unwrap().coords.len(), 50);
+
+            assert!(matches!(d.part1(&text), Answer::Numeric(_)));
+            assert!(matches!(d.part2(&text), Answer::Numeric(_)));
+        }
+
+        // Too few boxes for the puzzle's connections is an error, not a panic.
+        let text = Day8::new().generate(&mut Rng::new(0), 20).unwrap();
+        assert_eq!(Day8::new().try_part1(&text),
+                   Err(SolveError::Unsolvable(String::from("Only 20 junction boxes, fewer than the 1000 connections to make."))));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        let mut d = Day8::new();
+        d.to_connect(10);
+        fuzz::check(&d, &[EXAMPLE1]);
+    }
 }
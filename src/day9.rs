@@ -1,7 +1,14 @@
 use std::cmp::{max, min};
 
+use num_bigint::BigUint;
 use regex::Regex;
-use crate::day::{Day, Answer};
+use crate::arith::{self, Arith};
+use crate::day::{Day, Answer, SolveError, DEFAULT_STRATEGY};
+use crate::rng::Rng;
+use crate::trace;
+use crate::vis::{scale, Canvas, Style, Svg};
+
+const DAY: usize = 9;
 
 // A representation of the puzzle inputs.
 struct Input {
@@ -9,59 +16,294 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, SolveError>
     {
-        let line_re = Regex::new("([0-9]+),([0-9]+)").unwrap();
+        let line_re = Regex::new(Input::LINE_RE).unwrap();
         let mut coords = Vec::new();
 
-        for line in text.lines() {
+        for (line_no, line) in text.lines().enumerate() {
             // Process one line
-            if let Some(m) = line_re.captures(line) {
-                coords.push((m[1].parse::<usize>().unwrap(), 
-                             m[2].parse::<usize>().unwrap()));
+            coords.push(Input::parse_corner(&line_re, line_no + 1, line)?);
+        }
+
+        Ok(Input { coords })
+    }
+
+    const LINE_RE: &str = "([0-9]+),([0-9]+)";
+
+    // Parse one "x,y" line.  Lines are numbered from 1.
+    fn parse_corner(line_re: &Regex, line_no: usize, line: &str) -> Result<(usize, usize), SolveError> {
+        let m = line_re.captures(line)
+            .ok_or_else(|| { SolveError::parse(line_no, format!("Expected x,y: '{line}'")) })?;
+        let coord = |n: usize| {
+            m[n].parse::<usize>()
+                .map_err(|_| { SolveError::parse(line_no, format!("Coordinate too big: {}", &m[n])) })
+        };
+
+        Ok((coord(1)?, coord(2)?))
+    }
+
+    // Every line that isn't a corner, and every corner not in line with the one before it
+    // (treating the last corner as before the first, since the polygon is closed.)
+    fn lint(text: &str) -> Vec<SolveError> {
+        let line_re = Regex::new(Input::LINE_RE).unwrap();
+        let mut problems = Vec::new();
+        let mut corners = Vec::new();
+
+        for (line_no, line) in text.lines().enumerate() {
+            match Input::parse_corner(&line_re, line_no + 1, line) {
+                Ok(corner) => corners.push((line_no + 1, corner)),
+                Err(e) => problems.push(e),
             }
+        }
+        for n in 0..corners.len() {
+            let (_, c1) = corners[n];
+            let (line_no, c2) = corners[(n + 1) % corners.len()];
+            if c1.0 != c2.0 && c1.1 != c2.1 {
+                problems.push(SolveError::parse(line_no, "There shouldn't be any diagonal segments."));
+            }
+        }
+        problems.sort_by_key(|p| { p.line() });
+
+        problems
+    }
 
+    // Random input in the format read() accepts: a rectilinear polygon with about `size`
+    // corners (an even number, at least 4.)
+    //
+    // The polygon is a skyline: a run of columns of differing heights standing on a common base.
+    // Consecutive corners alternate horizontal and vertical edges and the outline never touches
+    // itself.  The result is randomly transposed so the base isn't always at the bottom.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const BASE: usize = 100_000;
+
+        let columns = max(size / 2, 2) - 1;
+        let mut xs = vec![rng.range(0, 1000)];
+        for _ in 0..columns {
+            let last = xs[xs.len()-1];
+            xs.push(last + rng.range(1, 2000));
+        }
+        let mut heights: Vec<usize> = Vec::new();
+        for _ in 0..columns {
+            let mut h = rng.range(0, BASE - 1);
+            while heights.last() == Some(&h) {
+                h = rng.range(0, BASE - 1);
+            }
+            heights.push(h);
         }
 
-        Input { coords }
+        let mut coords = vec![(xs[0], BASE)];
+        for (n, h) in heights.iter().enumerate() {
+            coords.push((xs[n], *h));
+            coords.push((xs[n+1], *h));
+        }
+        coords.push((xs[columns], BASE));
+
+        let transpose = rng.chance(1, 2);
+        let mut text = String::new();
+        for (x, y) in coords {
+            let (x, y) = if transpose { (y, x) } else { (x, y) };
+            text.push_str(&format!("{x},{y}\n"));
+        }
+
+        text
     }
 
-    fn biggest_rect(&self) -> usize {
-        let mut biggest_area = 1;
+    // Area of the rectangle with opposite corners c1 and c2, counting tiles.
+    fn area<T: Arith>(c1: (usize, usize), c2: (usize, usize)) -> Result<T, SolveError> {
+        let one = T::from_usize(1);
+        let dx = T::from_usize(c1.0.abs_diff(c2.0)).checked_add(&one);
+        let dy = T::from_usize(c1.1.abs_diff(c2.1)).checked_add(&one);
+        dx.zip(dy).and_then(|(dx, dy)| { dx.checked_mul(&dy) }).ok_or(SolveError::overflow(DAY, "rectangle area"))
+    }
+
+    // Part 2 treats the coords as the corners of a polygon, so each must be in line with the next.
+    fn check_corners(&self) -> Result<(), SolveError> {
+        for n in 0..self.coords.len() {
+            let np1 = (n + 1) % self.coords.len();
+            if self.coords[n].0 != self.coords[np1].0 && self.coords[n].1 != self.coords[np1].1 {
+                return Err(SolveError::parse(np1 + 1, "There shouldn't be any diagonal segments."));
+            }
+        }
 
-        for i in 0..self.coords.len()-1 {
+        Ok(())
+    }
+
+    fn biggest_rect<T: Arith>(&self) -> Result<T, SolveError> {
+        let mut biggest_area = T::from_usize(1);
+
+        for i in 0..self.coords.len() {
             for j in i+1..self.coords.len() {
-                let dx = self.coords[i].0.abs_diff(self.coords[j].0) + 1;
-                let dy = self.coords[i].1.abs_diff(self.coords[j].1) + 1;
-                let area = dx * dy;
+                let area: T = Input::area(self.coords[i], self.coords[j])?;
                 if area > biggest_area {
                     biggest_area = area;
                 }
             }
         }
 
-        biggest_area
+        Ok(biggest_area)
     }
 
-    fn biggest_rect2(&self) -> usize {
-        let mut biggest_area = 1;
-        let border = Border::new(&self);
+    fn biggest_rect2<T: Arith>(&self) -> Result<T, SolveError> {
+        Ok(self.best_rect2::<T>()?.0)
+    }
 
-        for i in 0..self.coords.len()-1 {
+    // The "border" strategy: the biggest rectangle none of the polygon's edges cut across.
+    //
+    // That's enough for the puzzle's input, a circle with a notch cut in it, but not for polygons
+    // in general.  A rectangle can span a dip in a skyline-shaped polygon with every edge of the
+    // dip inside it, or lie wholly outside a concave corner, and neither crosses an edge.
+    fn biggest_rect2_border<T: Arith>(&self) -> Result<T, SolveError> {
+        let mut biggest_area = T::from_usize(1);
+        let border = Border::new(self)?;
+
+        for i in 0..self.coords.len() {
             for j in i+1..self.coords.len() {
                 if !border.crosses(self.coords[i], self.coords[j]) {
-                    let dx = self.coords[i].0.abs_diff(self.coords[j].0) + 1;
-                    let dy = self.coords[i].1.abs_diff(self.coords[j].1) + 1;
-                    let area = dx * dy;
+                    let area: T = Input::area(self.coords[i], self.coords[j])?;
                     if area > biggest_area {
                         biggest_area = area;
-                        // println!("  Candidate: {i}, {j}");
                     }
                 }
             }
         }
 
-        biggest_area
+        Ok(biggest_area)
+    }
+
+    // The polygon, and the rectangle part 2 finds in it if there is one.
+    fn draw(&self, style: Style, rect: Option<(usize, usize)>) -> String {
+        const WIDTH: usize = 80;
+        const HEIGHT: usize = 40;
+        const SIZE: f64 = 800.0;
+
+        let min_x = self.coords.iter().map(|c| { c.0 }).min().unwrap_or(0);
+        let max_x = self.coords.iter().map(|c| { c.0 }).max().unwrap_or(0);
+        let min_y = self.coords.iter().map(|c| { c.1 }).min().unwrap_or(0);
+        let max_y = self.coords.iter().map(|c| { c.1 }).max().unwrap_or(0);
+        let rect = rect.map(|(i, j)| { (self.coords[i], self.coords[j]) });
+
+        match style {
+            Style::Ascii => {
+                let mut canvas = Canvas::new(WIDTH, HEIGHT);
+                let at = |(x, y): (usize, usize)| { (scale(x, min_x, max_x, WIDTH), scale(y, min_y, max_y, HEIGHT)) };
+                if let Some((c1, c2)) = rect {
+                    let ((x1, y1), (x2, y2)) = (at(c1), at(c2));
+                    for y in min(y1, y2)..=max(y1, y2) {
+                        for x in min(x1, x2)..=max(x1, x2) {
+                            canvas.set(x, y, 'O');
+                        }
+                    }
+                }
+                for n in 0..self.coords.len() {
+                    let (x1, y1) = at(self.coords[n]);
+                    let (x2, y2) = at(self.coords[(n + 1) % self.coords.len()]);
+                    for y in min(y1, y2)..=max(y1, y2) {
+                        for x in min(x1, x2)..=max(x1, x2) {
+                            canvas.set(x, y, '#');
+                        }
+                    }
+                }
+
+                canvas.render()
+            }
+            Style::Svg => {
+                let k = SIZE / (max(max_x - min_x, max_y - min_y).max(1)) as f64;
+                let at = |(x, y): (usize, usize)| { ((x - min_x) as f64 * k + 10.0, (y - min_y) as f64 * k + 10.0) };
+                let mut svg = Svg::new((max_x - min_x) as f64 * k + 20.0, (max_y - min_y) as f64 * k + 20.0);
+                let corners: Vec<(f64, f64)> = self.coords.iter().map(|c| { at(*c) }).collect();
+                svg.polygon(&corners, "#59a14f", "#e15759", 2.0);
+                if let Some((c1, c2)) = rect {
+                    let ((x1, y1), (x2, y2)) = (at(c1), at(c2));
+                    svg.rect(x1.min(x2), y1.min(y2), (x1 - x2).abs(), (y1 - y2).abs(), "#4e79a7");
+                }
+
+                svg.finish()
+            }
+        }
+    }
+
+    // The area biggest_rect2() finds, and the indices of the corners of that rectangle (None if
+    // no rectangle beats a single tile.)  This is the "compressed" strategy.
+    //
+    // Each distinct x and y coordinate gets an even grid index, with the odd indices standing for
+    // the gaps between them.  Draw the border on that grid, flood fill the outside from a margin
+    // around it, then use prefix sums of outside cells to test each rectangle in constant time.
+    fn best_rect2<T: Arith>(&self) -> Result<(T, Option<(usize, usize)>), SolveError> {
+        self.check_corners()?;
+
+        let mut xs: Vec<usize> = self.coords.iter().map(|c| { c.0 }).collect();
+        let mut ys: Vec<usize> = self.coords.iter().map(|c| { c.1 }).collect();
+        xs.sort();
+        xs.dedup();
+        ys.sort();
+        ys.dedup();
+
+        // Grid index of a coordinate, leaving one cell of margin on each side.
+        let gx = |x: usize| { 2 * xs.binary_search(&x).unwrap() + 1 };
+        let gy = |y: usize| { 2 * ys.binary_search(&y).unwrap() + 1 };
+        let width = 2 * xs.len() + 1;
+        let height = 2 * ys.len() + 1;
+
+        // Draw the border
+        let mut border = vec![vec![false; width]; height];
+        for n in 0..self.coords.len() {
+            let np1 = (n + 1) % self.coords.len();
+            let (x1, y1) = (gx(self.coords[n].0), gy(self.coords[n].1));
+            let (x2, y2) = (gx(self.coords[np1].0), gy(self.coords[np1].1));
+            for row in border.iter_mut().take(max(y1, y2) + 1).skip(min(y1, y2)) {
+                for cell in row.iter_mut().take(max(x1, x2) + 1).skip(min(x1, x2)) {
+                    *cell = true;
+                }
+            }
+        }
+
+        // Flood fill the outside, starting in the margin.
+        let mut outside = vec![vec![false; width]; height];
+        let mut to_visit: Vec<(usize, usize)> = vec![(0, 0)];
+        outside[0][0] = true;
+        while let Some((x, y)) = to_visit.pop() {
+            let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            for (nx, ny) in neighbors {
+                if nx < width && ny < height && !border[ny][nx] && !outside[ny][nx] {
+                    outside[ny][nx] = true;
+                    to_visit.push((nx, ny));
+                }
+            }
+        }
+
+        // outside_before[y][x] counts outside cells in rows < y and cols < x.
+        let mut outside_before = vec![vec![0_usize; width + 1]; height + 1];
+        for y in 0..height {
+            for x in 0..width {
+                outside_before[y + 1][x + 1] = outside_before[y][x + 1] + outside_before[y + 1][x]
+                    - outside_before[y][x] + outside[y][x] as usize;
+            }
+        }
+
+        let mut biggest_area = T::from_usize(1);
+        let mut best = None;
+        for i in 0..self.coords.len() {
+            for j in i+1..self.coords.len() {
+                let (x1, x2) = (gx(self.coords[i].0), gx(self.coords[j].0));
+                let (y1, y2) = (gy(self.coords[i].1), gy(self.coords[j].1));
+                let (left, right) = (min(x1, x2), max(x1, x2) + 1);
+                let (top, bottom) = (min(y1, y2), max(y1, y2) + 1);
+                let outside_cells = outside_before[bottom][right] + outside_before[top][left]
+                    - outside_before[top][right] - outside_before[bottom][left];
+
+                if outside_cells == 0 {
+                    let area: T = Input::area(self.coords[i], self.coords[j])?;
+                    if area > biggest_area {
+                        biggest_area = area;
+                        best = Some((i, j));
+                        trace::note(|| { format!("Candidate: {i}, {j}") });
+                    }
+                }
+            }
+        }
+
+        Ok((biggest_area, best))
     }
 }
 
@@ -71,7 +313,9 @@ struct Border {
 }
 
 impl Border {
-    fn new(input: &Input) -> Border {
+    fn new(input: &Input) -> Result<Border, SolveError> {
+        input.check_corners()?;
+
         // Construct horizontals and verticals
         let mut verticals = Vec::new();
         let mut horizontals = Vec::new();
@@ -86,7 +330,7 @@ impl Border {
 
                 verticals.push((x, min_y, max_y));
             }
-            else if input.coords[n].1 == input.coords[np1].1 {
+            else {
                 // Horizontal segment
                 let y = input.coords[n].1;
                 let min_x = min(input.coords[n].0, input.coords[np1].0);
@@ -94,12 +338,9 @@ impl Border {
 
                 horizontals.push((y, min_x, max_x));
             }
-            else {
-                panic!("There shouldn't be any diagonal segments.");
-            }
         }
 
-        Border { verticals, horizontals }
+        Ok(Border { verticals, horizontals })
     }
 
     fn crosses(&self, c1: (usize, usize), c2: (usize, usize)) -> bool {
@@ -154,26 +395,75 @@ impl Day9 {
 impl Day for Day9 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
+    fn try_part1(&self, text: &str) -> Result<Answer, SolveError> {
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
-        Answer::Numeric(input.biggest_rect())
+        arith::widen(|| { Ok(input.biggest_rect::<usize>()?.into_answer()) },
+                     || { Ok(input.biggest_rect::<u128>()?.into_answer()) },
+                     || { Ok(input.biggest_rect::<BigUint>()?.into_answer()) })
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn try_part2(&self, text: &str) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct
-        let input = Input::read(text);
+        let input = Input::read(text)?;
 
-        Answer::Numeric(input.biggest_rect2())
+        arith::widen(|| { Ok(input.biggest_rect2::<usize>()?.into_answer()) },
+                     || { Ok(input.biggest_rect2::<u128>()?.into_answer()) },
+                     || { Ok(input.biggest_rect2::<BigUint>()?.into_answer()) })
+    }
+
+    // "border" only holds for inputs shaped like the puzzle's (see biggest_rect2_border), so
+    // it's an alternative to check against, not the default.
+    fn strategies(&self, part: usize) -> Vec<&'static str> {
+        match part {
+            2 => vec!["compressed", "border"],
+            _ => vec![DEFAULT_STRATEGY],
+        }
+    }
+
+    fn solve_with(&self, part: usize, strategy: &str, text: &str) -> Result<Answer, SolveError> {
+        match (part, strategy) {
+            (1, DEFAULT_STRATEGY) => self.try_part1(text),
+            (2, DEFAULT_STRATEGY | "compressed") => self.try_part2(text),
+            (2, "border") => {
+                let input = Input::read(text)?;
+                arith::widen(|| { Ok(input.biggest_rect2_border::<usize>()?.into_answer()) },
+                             || { Ok(input.biggest_rect2_border::<u128>()?.into_answer()) },
+                             || { Ok(input.biggest_rect2_border::<BigUint>()?.into_answer()) })
+            }
+            _ => Err(SolveError::NoStrategy { part, strategy: strategy.to_string() }),
+        }
+    }
+
+    fn lint(&self, text: &str) -> Option<Vec<SolveError>> {
+        Some(Input::lint(text))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(Input::generate(rng, size))
+    }
+
+    // The polygon, then the biggest rectangle inside it.
+    fn visualize(&self, text: &str, style: Style) -> Option<Result<Vec<String>, SolveError>> {
+        let frames = Input::read(text)
+            .and_then(|input| { Ok((input.best_rect2::<u128>()?.1, input)) })
+            .map(|(best, input)| { vec![input.draw(style, None), input.draw(style, best)] });
+
+        Some(frames)
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::day9::{Day9, Input};
-    use crate::day::{Day, Answer};
+    use crate::day::{Day, Answer, SolveError, DEFAULT_STRATEGY};
+    use std::cmp::{max, min};
+
+    use crate::rng::Rng;
+    use crate::fuzz;
+    use crate::vis::Style;
     
     const EXAMPLE1: &str = "\
 7,1
@@ -189,7 +479,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.coords.len(), 8);
         assert_eq!(input.coords[0], (7, 1));
@@ -198,16 +488,44 @@ mod test {
 
     #[test]
     fn test_biggest_rect() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.biggest_rect(), 50);
+        assert_eq!(input.biggest_rect::<usize>(), Ok(50));
     }
 
     #[test]
     fn test_biggest_rect2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        assert_eq!(input.biggest_rect2::<usize>(), Ok(24));
+    }
+
+    #[test]
+    fn test_biggest_rect2_border() {
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        assert_eq!(input.biggest_rect2_border::<usize>(), Ok(24));
+    }
 
-        assert_eq!(input.biggest_rect2(), 24);
+    #[test]
+    fn test_best_rect2() {
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        assert_eq!(input.best_rect2::<usize>(), Ok((24, Some((4, 6)))));
+    }
+
+    #[test]
+    // The outline alone, then with the rectangle filled in.
+    fn test_visualize() {
+        let d = Day9::new();
+        let frames = d.visualize(EXAMPLE1, Style::Ascii).unwrap().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(!frames[0].contains('O'));
+        assert!(frames[1].contains('O'));
+
+        let svg = d.visualize(EXAMPLE1, Style::Svg).unwrap().unwrap();
+        assert!(svg[1].contains("<polygon points=\""));
+        assert!(svg[1].contains("<rect "));
     }
 
     #[test]
@@ -225,6 +543,110 @@ mod test {
         // Based on the example in part 2.
         let d = Day9::new();
         assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(24));
+
+        // The first strategy is the default, and both agree on the puzzle's shape.
+        assert_eq!(d.strategies(2), vec!["compressed", "border"]);
+        for strategy in d.strategies(2) {
+            assert_eq!(d.solve_with(2, strategy, EXAMPLE1), Ok(Answer::Numeric(24)));
+        }
+        assert_eq!(d.solve_with(2, DEFAULT_STRATEGY, EXAMPLE1), Ok(Answer::Numeric(24)));
+    }
+
+    #[test]
+    // Solve randomly generated inputs.  The best red and green rectangle is no bigger than the
+    // best rectangle overall.
+    // (The border strategy is left out: it relies on the shape of the puzzle input and gets
+    // skylines wrong.)
+    fn test_generate() {
+        let d = Day9::new();
+        for seed in 0..10 {
+            let text = d.generate(&mut Rng::new(seed), 20).unwrap();
+            let input = Input::read(&text).unwrap();
+            assert_eq!(input.coords.len(), 20);
+
+            let (Answer::Numeric(p1), Answer::Numeric(p2)) = (d.part1(&text), d.part2(&text)) else {
+                panic!("Expected numeric answers.");
+            };
+            assert!(p2 <= p1);
+        }
+    }
+
+    // Is the tile inside the polygon or on its border?
+    fn contains(coords: &[(usize, usize)], tile: (usize, usize)) -> bool {
+        let (x, y) = tile;
+        let mut crossings = 0;
+        for n in 0..coords.len() {
+            let (a, b) = (coords[n], coords[(n + 1) % coords.len()]);
+            if (min(a.0, b.0)..=max(a.0, b.0)).contains(&x) && (min(a.1, b.1)..=max(a.1, b.1)).contains(&y) {
+                return true;
+            }
+            // Count vertical edges to the right, treating each edge as half open.
+            if a.0 == b.0 && a.0 > x && (min(a.1, b.1)..max(a.1, b.1)).contains(&y) {
+                crossings += 1;
+            }
+        }
+
+        crossings % 2 == 1
+    }
+
+    // Biggest rectangle with red corners that only covers red or green tiles, checking every tile.
+    fn biggest_rect_by_tiles(coords: &[(usize, usize)]) -> usize {
+        let mut biggest_area = 1;
+        for i in 0..coords.len() {
+            for j in i+1..coords.len() {
+                let (x1, x2) = (min(coords[i].0, coords[j].0), max(coords[i].0, coords[j].0));
+                let (y1, y2) = (min(coords[i].1, coords[j].1), max(coords[i].1, coords[j].1));
+                let inside = (x1..=x2).all(|x| { (y1..=y2).all(|y| { contains(coords, (x, y)) }) });
+                if inside {
+                    biggest_area = max(biggest_area, (x2 - x1 + 1) * (y2 - y1 + 1));
+                }
+            }
+        }
+
+        biggest_area
+    }
+
+    #[test]
+    // Compare part 2 against checking every tile of every rectangle, on small random polygons.
+    // Generated polygons are shrunk by replacing each coordinate with twice its rank.
+    // (The border strategy is left out; it relies on the shape of the puzzle input.)
+    fn test_oracle() {
+        let d = Day9::new();
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(4, 14);
+            let text = d.generate(&mut rng, size).unwrap();
+            let coords = Input::read(&text).unwrap().coords;
+
+            let mut xs: Vec<usize> = coords.iter().map(|c| { c.0 }).collect();
+            let mut ys: Vec<usize> = coords.iter().map(|c| { c.1 }).collect();
+            xs.sort();
+            ys.sort();
+            let coords: Vec<(usize, usize)> = coords.iter()
+                .map(|c| { (2 * xs.binary_search(&c.0).unwrap(), 2 * ys.binary_search(&c.1).unwrap()) })
+                .collect();
+
+            let text: String = coords.iter().map(|(x, y)| { format!("{x},{y}\n") }).collect();
+            assert_eq!(d.try_part2(&text), Ok(Answer::Numeric(biggest_rect_by_tiles(&coords))), "coords {coords:?}");
+        }
+    }
+
+    #[test]
+    // Lint finds bad lines and the diagonal they leave behind.
+    fn test_lint() {
+        let d = Day9::new();
+        assert_eq!(d.lint(EXAMPLE1), Some(Vec::new()));
+
+        let bad = "7,1\n11,1\n11,7\nx\n9,5\n2,5\n2,3\n7,3\n";
+        assert_eq!(d.lint(bad), Some(vec![
+            SolveError::parse(4, "Expected x,y: 'x'"),
+            SolveError::parse(5, "There shouldn't be any diagonal segments."),
+        ]));
+    }
+
+    #[test]
+    // Mangled examples give an answer or an error, never a panic.
+    fn test_fuzz() {
+        fuzz::check(&Day9::new(), &[EXAMPLE1]);
     }
-    
 }
@@ -0,0 +1,69 @@
+// A quick fuzz pass that runs with the unit tests.
+//
+// Each day's test_fuzz mangles its examples a few hundred ways and checks that parsing and
+// solving never panic.  Errors are fine; panics, hangs and overflows aren't.  The libFuzzer
+// targets in fuzz/ do the same thing at length (see the README.)
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::day::{solve_all, Day};
+use crate::rng::Rng;
+
+// Mutations tried per example.
+const ROUNDS: usize = 300;
+
+// Bytes that mean something to at least one day's parser.
+const INTERESTING: &[u8] = b"0123456789 ,-:x#.^S+*[](){}\n";
+
+// Apply a few random edits to some text.
+fn mutate(rng: &mut Rng, text: &str) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+
+    for _ in 0..rng.range(1, 4) {
+        let at = rng.range(0, bytes.len());
+        match rng.below(6) {
+            0 if at < bytes.len() => {
+                // Flip a bit
+                bytes[at] ^= 1 << rng.below(8);
+            }
+            1 if at < bytes.len() => {
+                // Delete a byte
+                bytes.remove(at);
+            }
+            2 => {
+                // Insert a byte the parsers care about
+                bytes.insert(at, INTERESTING[rng.below(INTERESTING.len())]);
+            }
+            3 => {
+                // Insert a long run of digits
+                let digits: Vec<u8> = (0..rng.range(5, 30)).map(|_| { b'0' + rng.below(10) as u8 }).collect();
+                bytes.splice(at..at, digits);
+            }
+            4 => {
+                // Truncate
+                bytes.truncate(at);
+            }
+            _ => {
+                // Repeat a chunk
+                let end = rng.range(at, bytes.len());
+                let chunk = bytes[at..end].to_vec();
+                bytes.splice(end..end, chunk);
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Solve mutated versions of each example with every strategy, failing on the first panic.
+pub fn check(day: &dyn Day, examples: &[&str]) {
+    let mut rng = Rng::new(0);
+
+    for example in examples {
+        for _ in 0..ROUNDS {
+            let text = mutate(&mut rng, example);
+            let result = catch_unwind(AssertUnwindSafe(|| { solve_all(day, &text) }));
+            assert!(result.is_ok(), "Panicked on input:\n{text:?}");
+        }
+    }
+}
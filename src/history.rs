@@ -0,0 +1,194 @@
+// A history of runs, for spotting parts that got slower or changed their answers.
+//
+// Each recorded part is a line in HISTORY_FILE:
+//
+//   <unix time> <commit> <day> <part> <micros> <answer>
+//
+// The answer is the rest of the line ("error" if the part failed.)  The file only ever grows, so
+// older runs stay around to compare against.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::process::Command;
+use std::time::{Instant, SystemTime};
+
+use rs_aoc2025::day::Day;
+
+use crate::dash::format_micros;
+
+pub const HISTORY_FILE: &str = "rs_aoc2025_history.txt";
+
+// Parts faster than this in both runs aren't timed closely enough to call slower.
+const MIN_MICROS: u128 = 1_000;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub time: u64,
+    pub commit: String,
+    pub day: usize,
+    pub part: usize,
+    pub micros: u128,
+    pub answer: String,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.splitn(6, ' ');
+        let time = fields.next()?.parse().ok()?;
+        let commit = fields.next()?.to_string();
+        let day = fields.next()?.parse().ok()?;
+        let part = fields.next()?.parse().ok()?;
+        let micros = fields.next()?.parse().ok()?;
+        let answer = fields.next()?.to_string();
+
+        Some(Entry { time, commit, day, part, micros, answer })
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {} {} {} {}", self.time, self.commit, self.day, self.part, self.micros, self.answer)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Change {
+    // Took more than the threshold longer than the baseline.
+    Slower { was: u128, now: u128 },
+
+    // Gave a different answer from the baseline.
+    Answer { was: String, now: String },
+}
+
+// The entries in a history file.  Lines that don't parse are skipped.
+pub fn load(text: &str) -> Vec<Entry> {
+    text.lines().filter_map(Entry::parse).collect()
+}
+
+// Add entries to the history file.
+pub fn append(entries: &[Entry]) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(HISTORY_FILE)?;
+    for entry in entries {
+        writeln!(file, "{entry}")?;
+    }
+
+    Ok(())
+}
+
+// The commit being run, marked "-dirty" if there are uncommitted changes.
+pub fn commit() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|out| { out.status.success() })
+        .and_then(|out| { String::from_utf8(out.stdout).ok() })
+        .map(|s| { s.trim().to_string() })
+        .filter(|s| { !s.is_empty() && !s.contains(' ') })
+        .unwrap_or(String::from("unknown"))
+}
+
+// Solve and time both parts of each day wanted (all of them if `day` is None.)
+pub fn run(days: &[(&dyn Day, &str)], day: Option<usize>) -> Vec<Entry> {
+    let time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| { d.as_secs() });
+    let commit = commit();
+
+    let mut entries = Vec::new();
+    for (n, (d, text)) in days.iter().enumerate() {
+        if day.is_some_and(|wanted| { wanted != n + 1 }) {
+            continue;
+        }
+        for part in 1..=2 {
+            let start = Instant::now();
+            let result = if part == 1 { d.try_part1(text) } else { d.try_part2(text) };
+            let micros = start.elapsed().as_micros();
+            let answer = match result {
+                Ok(ans) => ans.to_string(),
+                Err(_) => String::from("error"),
+            };
+            entries.push(Entry { time, commit: commit.clone(), day: n + 1, part, micros, answer });
+        }
+    }
+
+    entries
+}
+
+// The latest entry for a part, from a given commit if there is one.
+pub fn baseline<'a>(history: &'a [Entry], day: usize, part: usize, commit: Option<&str>) -> Option<&'a Entry> {
+    history.iter()
+        .filter(|e| { e.day == day && e.part == part })
+        .filter(|e| { commit.is_none_or(|c| { e.commit == c }) })
+        .max_by_key(|e| { e.time })
+}
+
+// How a part's run differs from its baseline.  `threshold` is the slowdown allowed, in percent.
+pub fn compare(was: &Entry, now: &Entry, threshold: u32) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if now.answer != was.answer {
+        changes.push(Change::Answer { was: was.answer.clone(), now: now.answer.clone() });
+    }
+    let limit = was.micros + was.micros * threshold as u128 / 100;
+    if now.micros > limit && now.micros.max(was.micros) >= MIN_MICROS {
+        changes.push(Change::Slower { was: was.micros, now: now.micros });
+    }
+
+    changes
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Change::Slower { was, now } => {
+                write!(f, "slower: {} -> {} (+{}%)", format_micros(*was), format_micros(*now),
+                       (now - was) * 100 / (*was).max(1))
+            }
+            Change::Answer { was, now } => write!(f, "answer changed: {was} -> {now}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::history::{baseline, compare, load, Change, Entry};
+
+    fn entry(time: u64, commit: &str, micros: u128, answer: &str) -> Entry {
+        Entry { time, commit: commit.to_string(), day: 10, part: 2, micros, answer: answer.to_string() }
+    }
+
+    #[test]
+    fn test_load() {
+        // The answer is the rest of the line, spaces and all.
+        let history = load("100 abc123 10 2 5400 21467\nnonsense\n200 def456-dirty 10 2 6100 two words\n");
+        assert_eq!(history, vec![
+            entry(100, "abc123", 5400, "21467"),
+            entry(200, "def456-dirty", 6100, "two words"),
+        ]);
+        assert_eq!(load(&format!("{}\n", history[1])), vec![history[1].clone()]);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let history = vec![entry(300, "b", 1, "x"), entry(100, "a", 2, "x"), entry(200, "a", 3, "x")];
+        assert_eq!(baseline(&history, 10, 2, None), Some(&history[0]));
+        assert_eq!(baseline(&history, 10, 2, Some("a")), Some(&history[2]));
+        assert_eq!(baseline(&history, 10, 2, Some("c")), None);
+        assert_eq!(baseline(&history, 10, 1, None), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let was = entry(100, "a", 10_000, "21467");
+        assert_eq!(compare(&was, &entry(200, "b", 12_000, "21467"), 25), vec![]);
+        assert_eq!(compare(&was, &entry(200, "b", 13_000, "21467"), 25),
+                   vec![Change::Slower { was: 10_000, now: 13_000 }]);
+        assert_eq!(compare(&was, &entry(200, "b", 9_000, "21288"), 25),
+                   vec![Change::Answer { was: "21467".to_string(), now: "21288".to_string() }]);
+
+        // Tiny parts are too noisy to call slower.
+        assert_eq!(compare(&entry(100, "a", 20, "6"), &entry(200, "b", 90, "6"), 25), vec![]);
+        assert_eq!(Change::Slower { was: 10_000, now: 13_000 }.to_string(), "slower: 10.0 ms -> 13.0 ms (+30%)");
+    }
+}
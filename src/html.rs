@@ -0,0 +1,202 @@
+// A self-contained HTML report of a run of every day: answers checked against the expected ones,
+// times as a bar chart, peak heap use, the trace notes the solvers made, and pictures from the
+// days that draw them.
+//
+// Everything (styles included) is in the one file, so it can be published as it is.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Instant;
+
+use rs_aoc2025::day::{Answer, Day, SolveError};
+use rs_aoc2025::trace;
+use rs_aoc2025::vis::Style;
+
+use crate::dash::format_micros;
+use crate::memory::{self, format_bytes};
+
+pub const REPORT_FILE: &str = "rs_aoc2025_report.html";
+
+// Trace notes shown per part.  Past this they're counted, not shown.
+const MAX_NOTES_SHOWN: usize = 200;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { padding: 0.25em 0.75em; text-align: left; border-bottom: 1px solid #ddd; }
+td.num { text-align: right; font-family: monospace; }
+.ok { color: #1a7f37; }
+.wrong { color: #cf222e; font-weight: bold; }
+.bar { background: #54aeff; height: 0.9em; }
+pre { background: #f6f8fa; padding: 0.5em; overflow-x: auto; }";
+
+pub struct PartRun {
+    pub day: usize,
+    pub part: usize,
+    pub result: Result<Answer, SolveError>,
+    pub expected: Answer,
+    pub micros: u128,
+    pub bytes: usize,
+    pub notes: Vec<String>,
+}
+
+impl PartRun {
+    pub fn passed(&self) -> bool {
+        self.result.as_ref().is_ok_and(|ans| { *ans == self.expected })
+    }
+}
+
+// Solve every part of every day, one at a time so the heap figures are each part's own.
+pub fn run(days: &[(&dyn Day, &str)], expected: &[(Answer, Answer)]) -> Vec<PartRun> {
+    let mut runs = Vec::new();
+    for (n, (day, text)) in days.iter().enumerate() {
+        for part in 1..=2 {
+            let start = Instant::now();
+            let ((result, notes), bytes) = memory::peak(|| {
+                trace::capture(|| {
+                    catch_unwind(AssertUnwindSafe(|| {
+                        if part == 1 { day.try_part1(text) } else { day.try_part2(text) }
+                    }))
+                    .unwrap_or_else(|_| { Err(SolveError::Unsolvable(String::from("The solver panicked."))) })
+                })
+            });
+            let micros = start.elapsed().as_micros();
+            let expected = if part == 1 { expected[n].0.clone() } else { expected[n].1.clone() };
+
+            runs.push(PartRun { day: n + 1, part, result, expected, micros, bytes, notes });
+        }
+    }
+
+    runs
+}
+
+// The final SVG frame of each day that draws one, by day number.
+pub fn pictures(days: &[(&dyn Day, &str)]) -> Vec<(usize, String)> {
+    days.iter().enumerate()
+        .filter_map(|(n, (day, text))| {
+            let frame = day.visualize(text, Style::Svg)?.ok()?.pop()?;
+            Some((n + 1, frame))
+        })
+        .collect()
+}
+
+// Escape text for HTML.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+// Width of a time's bar, in percent of the slowest.  Times span microseconds to seconds, so the
+// scale is logarithmic.
+fn bar_width(micros: u128, slowest: u128) -> f64 {
+    if slowest == 0 {
+        return 0.0;
+    }
+    100.0 * ((micros + 1) as f64).ln() / ((slowest + 1) as f64).ln()
+}
+
+// The report, as a complete HTML page.
+pub fn render(runs: &[PartRun], pictures: &[(usize, String)], commit: &str) -> String {
+    let passed = runs.iter().filter(|r| { r.passed() }).count();
+    let total_micros: u128 = runs.iter().map(|r| { r.micros }).sum();
+    let slowest = runs.iter().map(|r| { r.micros }).max().unwrap_or(0);
+
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                            <title>Advent of Code 2025</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n\
+                            <h1>Advent of Code 2025</h1>\n");
+    html.push_str(&format!("<p>Commit {}: {passed} of {} parts match the expected answers, in {} altogether.</p>\n",
+                           escape(commit), runs.len(), format_micros(total_micros)));
+
+    html.push_str("<table>\n<tr><th>Day</th><th>Part</th><th>Answer</th><th>Expected</th><th>Status</th>\
+                   <th>Time</th><th>Time (log scale)</th><th>Peak heap</th></tr>\n");
+    for r in runs {
+        let (answer, status) = match &r.result {
+            Ok(ans) if r.passed() => (ans.to_string(), "<span class=\"ok\">ok</span>"),
+            Ok(ans) => (ans.to_string(), "<span class=\"wrong\">WRONG</span>"),
+            Err(e) => (format!("error: {e}"), "<span class=\"wrong\">failed</span>"),
+        };
+        html.push_str(&format!("<tr><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+                                <td class=\"num\">{}</td><td>{status}</td><td class=\"num\">{}</td>\
+                                <td style=\"width: 20em\"><div class=\"bar\" style=\"width: {:.1}%\"></div></td>\
+                                <td class=\"num\">{}</td></tr>\n",
+                               r.day, r.part, escape(&answer), escape(&r.expected.to_string()),
+                               format_micros(r.micros), bar_width(r.micros, slowest), format_bytes(r.bytes)));
+    }
+    html.push_str("</table>\n");
+
+    let traced: Vec<&PartRun> = runs.iter().filter(|r| { !r.notes.is_empty() }).collect();
+    if !traced.is_empty() {
+        html.push_str("<h2>Trace notes</h2>\n");
+        for r in traced {
+            let mut shown: Vec<String> = r.notes.iter().take(MAX_NOTES_SHOWN).map(|n| { escape(n) }).collect();
+            if r.notes.len() > MAX_NOTES_SHOWN {
+                shown.push(format!("({} more)", r.notes.len() - MAX_NOTES_SHOWN));
+            }
+            html.push_str(&format!("<details><summary>Day {} part {} ({} notes)</summary>\n<pre>{}</pre>\n</details>\n",
+                                   r.day, r.part, r.notes.len(), shown.join("\n")));
+        }
+    }
+
+    if !pictures.is_empty() {
+        html.push_str("<h2>Pictures</h2>\n");
+        for (day, svg) in pictures {
+            html.push_str(&format!("<details><summary>Day {day}</summary>\n{svg}</details>\n"));
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+#[cfg(test)]
+mod test {
+    use rs_aoc2025::day::{Answer, SolveError};
+
+    use crate::html::{bar_width, escape, render, PartRun};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<b>\"R&D\"</b>'s"), "&lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt;&#39;s");
+    }
+
+    #[test]
+    fn test_bar_width() {
+        assert_eq!(bar_width(0, 0), 0.0);
+        assert_eq!(bar_width(999, 999), 100.0);
+        assert_eq!(bar_width(0, 999), 0.0);
+        assert!((bar_width(31, 1023) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_render() {
+        let run = |part: usize, result: Result<Answer, SolveError>, notes: Vec<String>| {
+            PartRun { day: 1, part, result, expected: Answer::Numeric(6), micros: 1500, bytes: 2048, notes }
+        };
+        let runs = vec![
+            run(1, Ok(Answer::Numeric(6)), vec![String::from("dial at <0>")]),
+            run(2, Err(SolveError::parse(3, "Bad move 'X1'")), vec![]),
+        ];
+        let pictures = vec![(4, String::from("<svg></svg>\n"))];
+        let html = render(&runs, &pictures, "713a53d");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<p>Commit 713a53d: 1 of 2 parts match the expected answers, in 3.0 ms altogether.</p>"));
+        assert!(html.contains("<span class=\"ok\">ok</span>"));
+        assert!(html.contains("error: line 3: Bad move &#39;X1&#39;"));
+        assert!(html.contains("<td class=\"num\">2.0 KiB</td>"));
+        assert!(html.contains("<summary>Day 1 part 1 (1 notes)</summary>\n<pre>dial at &lt;0&gt;</pre>"));
+        assert!(!html.contains("Day 1 part 2 ("));
+        assert!(html.contains("<details><summary>Day 4</summary>\n<svg></svg>\n</details>"));
+    }
+}
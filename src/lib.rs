@@ -1,3 +1,25 @@
-pub fn test() {
-    println!("Test");
-}
+// The puzzle solutions, as a library so the fuzz targets in fuzz/ can drive them.
+
+// Days are built by const fn new() for main's DAYS table, which Default can't do.
+#![allow(clippy::new_without_default)]
+
+pub mod arith;
+pub mod day;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod rng;
+pub mod trace;
+pub mod vis;
+
+#[cfg(test)]
+mod fuzz;
@@ -1,32 +1,36 @@
 // #![feature(random)]
 
-mod day;
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-
-use day::{Day, Answer};
-use day1::Day1;
-use day2::Day2;
-use day3::Day3;
-use day4::Day4;
-use day5::Day5;
-use day6::Day6;
-use day7::Day7;
-use day8::Day8;
-use day9::Day9;
-use day10::Day10;
-use day11::Day11;
-use day12::Day12;
+mod cli;
+mod dash;
+mod history;
+mod html;
+mod memory;
+mod repl;
+mod server;
+mod watch;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use cli::{Command, IdKind};
+use rs_aoc2025::arith;
+use rs_aoc2025::day::{Day, Answer, SolveError};
+use rs_aoc2025::day1::Day1;
+use rs_aoc2025::day2::{Day2, NonDecreasing, Overlaps, Palindromes, Pattern, Predicate, Rule};
+use rs_aoc2025::day3::Day3;
+use rs_aoc2025::day4::Day4;
+use rs_aoc2025::day5::Day5;
+use rs_aoc2025::day6::Day6;
+use rs_aoc2025::day7::Day7;
+use rs_aoc2025::day8::Day8;
+use rs_aoc2025::day9::Day9;
+use rs_aoc2025::day10::Day10;
+use rs_aoc2025::day11::Day11;
+use rs_aoc2025::day12::Day12;
+use rs_aoc2025::rng::Rng;
+use rs_aoc2025::vis::Style;
 
 use data_aoc2025::DAY1_INPUT;
 use data_aoc2025::DAY2_INPUT;
@@ -41,6 +45,10 @@ use data_aoc2025::DAY10_INPUT;
 use data_aoc2025::DAY11_INPUT;
 use data_aoc2025::DAY12_INPUT;
 
+// Counts heap use, for the HTML report.
+#[global_allocator]
+static ALLOCATOR: memory::Counter = memory::Counter;
+
 static DAYS: [(&dyn Day, &str); 12] = [
     (&Day1::new(), DAY1_INPUT),  // Dec 1
     (&Day2::new(), DAY2_INPUT),
@@ -56,47 +64,469 @@ static DAYS: [(&dyn Day, &str); 12] = [
     (&Day12::new(), DAY12_INPUT),
 ];
 
-fn report_day(day_no: usize) {
-    
+// The right answers for the puzzle inputs in DAYS.
+static EXPECTED: [(Answer, Answer); 12] = [
+    (Answer::Numeric(1105), Answer::Numeric(6599)),                 // Dec 1
+    (Answer::Numeric(21139440284), Answer::Numeric(38731915928)),   // Dec 2
+    (Answer::Numeric(17193), Answer::Numeric(171297349921310)),     // Dec 3
+    (Answer::Numeric(1424), Answer::Numeric(8727)),                 // Dec 4
+    (Answer::Numeric(737), Answer::Numeric(357485433193284)),       // Dec 5
+    (Answer::Numeric(5733696195703), Answer::Numeric(10951882745757)),   // Dec 6
+    (Answer::Numeric(1524), Answer::Numeric(32982105837605)),   // Dec 7
+    (Answer::Numeric(50568), Answer::Numeric(36045012)),   // Dec 8
+    (Answer::Numeric(4744899849), Answer::Numeric(1540192500)),   // Part 1 > 4707216618
+    (Answer::Numeric(502), Answer::Numeric(21467)),   // Dec 10 21932 is too high, 21288 too low.
+    (Answer::Numeric(690), Answer::Numeric(557332758684000)),   // Dec 11
+    (Answer::Numeric(577), Answer::None),   // Dec 12)
+];
+
+// Print both answers for a day, solving `text`.
+// With a strategy, parts that don't register it are shown as "-".
+// Parts that fail show "error", with the reason on stderr.
+// When checking, each answer is followed by "ok", or the answer expected.
+fn report_day(day_no: usize, part: Option<usize>, strategy: Option<&str>, text: &str, check: bool) {
+
+    let (day, _) = DAYS[day_no-1];
+    let expected = &EXPECTED[day_no-1];
+
+    let msgs: Vec<String> = (1..=2)
+        .map(|p| {
+            if part.is_some_and(|wanted| { wanted != p }) {
+                return String::from("-");
+            }
+
+            let result = match strategy {
+                Some(name) => day.solve_with(p, name, text),
+                None if p == 1 => day.try_part1(text),
+                None => day.try_part2(text),
+            };
+            match result {
+                Ok(ans) if check => {
+                    let want = if p == 1 { &expected.0 } else { &expected.1 };
+                    if ans == *want { format!("{ans} ok") } else { format!("{ans} (want {want})") }
+                }
+                Ok(ans) => format!("{ans}"),
+                Err(SolveError::NoStrategy { .. }) => format!("{}", Answer::None),
+                Err(e) => {
+                    eprintln!("Day {day_no} part {p}: {e}");
+                    String::from("error")
+                }
+            }
+        })
+        .collect();
+
+    println!("Day {day_no:2}: {:>18} {:>18}", msgs[0], msgs[1]);
+}
+
+// Run every strategy registered for each part of a day on the same input.
+// Parts with only one strategy have nothing to compare and are skipped.
+// A strategy that fails counts as its own answer, so it disagrees with any that succeed.
+// Returns the number of parts where the strategies disagree.
+fn diff_day(day_no: usize) -> usize {
     let (day, text) = DAYS[day_no-1];
+    let mut disagreements = 0;
+
+    for part in 1..=2 {
+        let strategies = day.strategies(part);
+        if strategies.len() < 2 {
+            continue;
+        }
 
-    let ans1 = day.part1(text);
-    let msg1 = match ans1 {
-        Answer::None => String::from("        -"),
-        Answer::Numeric(n) => format!("{n}"),
-        Answer::String(s) => format!("{s}"),
+        let results: Vec<(&str, Result<Answer, SolveError>)> = strategies.into_iter()
+            .map(|name| { (name, day.solve_with(part, name, text)) })
+            .collect();
+
+        let agree = results.windows(2).all(|w| { w[0].1 == w[1].1 });
+        let answers: Vec<String> = results.iter()
+            .map(|(name, result)| {
+                match result {
+                    Ok(ans) => format!("{name}={ans}"),
+                    Err(e) => format!("{name}=error({e})"),
+                }
+            })
+            .collect();
+
+        let verdict = if agree { "ok" } else { "DISAGREE" };
+        println!("Day {day_no:2} part {part}: {:8} {}", verdict, answers.join(" "));
+
+        if !agree {
+            disagreements += 1;
+        }
+    }
+
+    disagreements
+}
+
+// Solve one part of a day, reading the input from a file (or stdin) as it goes.
+// Returns false if there's no answer.
+fn stream_day(day_no: usize, part: usize, file: Option<&str>) -> bool {
+    let (day, _) = DAYS[day_no-1];
+
+    let mut reader: Box<dyn BufRead> = match file {
+        Some(path) => match File::open(path) {
+            Ok(f) => Box::new(BufReader::new(f)),
+            Err(e) => {
+                println!("Can't open {path}: {e}");
+                return false;
+            }
+        },
+        None => Box::new(std::io::stdin().lock()),
     };
 
-    let ans2 = day.part2(text);
-    let msg2 = match ans2 {
-        Answer::None => String::from("        -"),
-        Answer::Numeric(n) => format!("{n}"),
-        Answer::String(s) => format!("{s}"),
+    match day.solve_stream(part, &mut reader) {
+        Some(Ok(ans)) => {
+            println!("Day {day_no:2} part {part}: {ans}");
+            true
+        }
+        Some(Err(e)) => {
+            println!("Day {day_no} part {part}: {e}");
+            false
+        }
+        None => {
+            println!("Day {day_no} can't stream part {part}.");
+            false
+        }
+    }
+}
+
+// Check an input against a day's assumptions, printing every problem found.
+// The input is read from a file, or is the day's puzzle input.  Returns false if there are problems.
+fn lint_day(day_no: usize, file: Option<&str>) -> bool {
+    let (day, puzzle_input) = DAYS[day_no-1];
+
+    let text = match file {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("Can't read {path}: {e}");
+                return false;
+            }
+        },
+        None => puzzle_input.to_string(),
     };
-    println!("Day {day_no:2}: {msg1:>18} {msg2:>18}");
+
+    match day.lint(&text) {
+        Some(problems) if problems.is_empty() => {
+            println!("Day {day_no}: no problems found.");
+            true
+        }
+        Some(problems) => {
+            for problem in &problems {
+                println!("Day {day_no}: {problem}");
+            }
+            println!("\n{} problem(s).", problems.len());
+            false
+        }
+        None => {
+            println!("Day {day_no} has no lint checks.");
+            false
+        }
+    }
 }
 
+fn day2_predicate(base: u32, kind: &IdKind) -> Result<Box<dyn Predicate>, SolveError> {
+    let predicate: Box<dyn Predicate> = match kind {
+        IdKind::Repeats { min_repeats, max_repeats, exact } => Box::new(Rule::new(base, *min_repeats, *max_repeats, *exact)?),
+        IdKind::Palindromes => Box::new(Palindromes::new(base)?),
+        IdKind::NonDecreasing => Box::new(NonDecreasing::new(base)?),
+        IdKind::Pattern(pattern) => Box::new(Pattern::new(base, pattern)?),
+    };
+
+    Ok(predicate)
+}
+
+// Count and sum day 2's invalid ids under another rule, in a file or the puzzle input, and
+// range by range when `breakdown`.
+fn day2_ids(file: Option<&str>, base: u32, kind: &IdKind, overlaps: Overlaps, breakdown: bool) -> bool {
+    let text = match file {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("Can't read {path}: {e}");
+                return false;
+            }
+        },
+        None => DAY2_INPUT.to_string(),
+    };
+
+    let day = Day2::new().with_overlaps(overlaps);
+    let counted = day2_predicate(base, kind).and_then(|predicate| {
+        if breakdown {
+            for range in day.breakdown(&text, predicate.as_ref())? {
+                println!("line {}: {}-{}: {} ids, summing to {}", range.line, range.low, range.high, range.tally.count, range.tally.sum);
+            }
+        }
+        let (count, sum) = day.count_and_sum_matching(&text, predicate.as_ref())?;
+        Ok((predicate, count, sum))
+    });
+    match counted {
+        Ok((predicate, count, sum)) => {
+            println!("{predicate}: {count} ids, summing to {sum}.");
+            true
+        }
+        Err(e) => {
+            println!("{e}");
+            false
+        }
+    }
+}
+
+// Time every part of a day (or all of them) and add the run to the history.
+fn record(day: Option<usize>) -> bool {
+    let entries = history::run(&DAYS, day);
+    for e in &entries {
+        println!("Day {:2} part {}: {:>18} {:>10}", e.day, e.part, e.answer, dash::format_micros(e.micros));
+    }
+
+    match history::append(&entries) {
+        Ok(()) => {
+            let commit = entries.first().map_or(String::new(), |e| { e.commit.clone() });
+            println!("\nRecorded {} part(s) at {commit} in {}.", entries.len(), history::HISTORY_FILE);
+            true
+        }
+        Err(e) => {
+            println!("Can't write {}: {e}", history::HISTORY_FILE);
+            false
+        }
+    }
+}
+
+// Time parts again and report any that are slower than the history, or answer differently.
+// Returns the number of parts that changed.
+fn compare(day: Option<usize>, threshold: u32, against: Option<&str>) -> usize {
+    let history = history::load(&std::fs::read_to_string(history::HISTORY_FILE).unwrap_or_default());
+    let mut changed = 0;
+
+    for now in history::run(&DAYS, day) {
+        let label = format!("Day {:2} part {}:", now.day, now.part);
+        let Some(was) = history::baseline(&history, now.day, now.part, against) else {
+            println!("{label} no baseline ({} {})", now.answer, dash::format_micros(now.micros));
+            continue;
+        };
+
+        let changes = history::compare(was, &now, threshold);
+        if changes.is_empty() {
+            println!("{label} ok ({} vs {} at {})", dash::format_micros(now.micros), dash::format_micros(was.micros), was.commit);
+        }
+        else {
+            for change in &changes {
+                println!("{label} {change} (since {})", was.commit);
+            }
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+// Draw a day working through an input, from a file or the day's puzzle input.  The last frame
+// goes to stdout, or with a directory, every frame goes to a numbered file there.
+// Returns false if there's nothing to show.
+fn vis_day(day_no: usize, file: Option<&str>, style: Style, frames_dir: Option<&str>) -> bool {
+    let (day, puzzle_input) = DAYS[day_no-1];
+
+    let text = match file {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("Can't read {path}: {e}");
+                return false;
+            }
+        },
+        None => puzzle_input.to_string(),
+    };
+
+    let frames = match day.visualize(&text, style) {
+        Some(Ok(frames)) => frames,
+        Some(Err(e)) => {
+            println!("Day {day_no}: {e}");
+            return false;
+        }
+        None => {
+            println!("Day {day_no} has no visualization.");
+            return false;
+        }
+    };
+
+    match frames_dir {
+        Some(dir) => {
+            let written = std::fs::create_dir_all(dir).and_then(|_| {
+                frames.iter().enumerate().try_for_each(|(n, frame)| {
+                    std::fs::write(format!("{dir}/frame_{n:04}.{}", style.extension()), frame)
+                })
+            });
+            match written {
+                Ok(()) => {
+                    println!("Wrote {} frame(s) to {dir}.", frames.len());
+                    true
+                }
+                Err(e) => {
+                    println!("Can't write frames to {dir}: {e}");
+                    false
+                }
+            }
+        }
+        None => {
+            print!("{}", frames.last().map_or("", |frame| { frame.as_str() }));
+            true
+        }
+    }
+}
 
 fn main() {
-    println!("Advent of Code 2025!\n");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::take_overflow(&mut args)
+        .map(arith::set_policy)
+        .and_then(|_| { cli::parse(&args, DAYS.len()) });
+
+    // The dashboard takes over the terminal.
+    if let Ok(Command::Dash { timeout }) = command {
+        if let Err(e) = dash::run(&DAYS, &EXPECTED, Duration::from_secs(timeout)) {
+            eprintln!("Dashboard failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Watching runs until it's interrupted.
+    if let Ok(Command::Watch { day, file }) = &command {
+        watch::run(*day, file.as_deref());
+    }
 
-    let target_day = 0;
+    // So do pictures.
+    if let Ok(Command::Vis { day, file, style, frames }) = &command {
+        if !vis_day(*day, file.as_deref(), *style, frames.as_deref()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Generated inputs go to stdout on their own so they can be redirected to a file.
+    if let Ok(Command::Generate { day: day_no, size, seed }) = command {
+        let (day, _) = DAYS[day_no-1];
+        match day.generate(&mut Rng::new(seed), size) {
+            Some(text) => print!("{text}"),
+            None => {
+                eprintln!("Day {day_no} has no input generator.");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    println!("Advent of Code 2025!\n");
 
-    match target_day {
-        0 => {
+    match command {
+        Ok(Command::Report { day: None, check, .. }) => {
             // report all days
             println!("{:7} {:>18} {:>18}", "", "Part 1", "Part 2");
-            for day_no in 1..=12 {
-                report_day(day_no);
+            for (day_no, (_, text)) in DAYS.iter().enumerate() {
+                report_day(day_no + 1, None, None, text, check);
             }
         }
-        1..=25 => {
+        Ok(Command::Report { day: Some(day_no), part, strategy, input, check }) => {
             // report a specific day
-            report_day(target_day);
+            if let Some(name) = &strategy {
+                let (day, _) = DAYS[day_no-1];
+                let registered = (1..=2)
+                    .filter(|p| { part.is_none_or(|wanted| { wanted == *p }) })
+                    .any(|p| { day.strategies(p).contains(&name.as_str()) });
+                if !registered {
+                    println!("Day {day_no} has no strategy '{name}'.\n");
+                    std::process::exit(2);
+                }
+            }
+            let text = match &input {
+                Some(path) => match std::fs::read_to_string(path) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        println!("Can't read {path}: {e}\n");
+                        std::process::exit(1);
+                    }
+                },
+                None => DAYS[day_no-1].1.to_string(),
+            };
+            report_day(day_no, part, strategy.as_deref(), &text, check);
+        }
+        Ok(Command::Diff { day }) => {
+            let days = match day {
+                Some(day_no) => day_no..=day_no,
+                None => 1..=DAYS.len(),
+            };
+            let disagreements: usize = days.map(diff_day).sum();
+            println!();
+            if disagreements > 0 {
+                println!("{disagreements} part(s) with disagreeing strategies.\n");
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Generate { .. }) | Ok(Command::Dash { .. }) | Ok(Command::Watch { .. }) | Ok(Command::Vis { .. }) => {
+            // handled above
         }
-        _ => {
-            // invalid day 
-            println!("Day {target_day} is invalid.\n");
+        Ok(Command::Stream { day, part, file }) => {
+            if !stream_day(day, part, file.as_deref()) {
+                println!();
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Serve { port }) => {
+            match TcpListener::bind(("127.0.0.1", port)) {
+                Ok(listener) => {
+                    println!("Serving on http://127.0.0.1:{port}/ (GET /days, POST /solve/<day>/<part>)");
+                    server::serve(listener, &DAYS);
+                }
+                Err(e) => {
+                    println!("Can't listen on port {port}: {e}\n");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Ok(Command::Record { day }) => {
+            if !record(day) {
+                println!();
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Compare { day, threshold, against }) => {
+            let changed = compare(day, threshold, against.as_deref());
+            println!();
+            if changed > 0 {
+                println!("{changed} part(s) changed.\n");
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Html { file }) => {
+            let runs = html::run(&DAYS, &EXPECTED);
+            let pictures = html::pictures(&DAYS);
+            let passed = runs.iter().filter(|r| { r.passed() }).count();
+            if let Err(e) = std::fs::write(&file, html::render(&runs, &pictures, &history::commit())) {
+                println!("Can't write {file}: {e}\n");
+                std::process::exit(1);
+            }
+            println!("Wrote {file}: {passed} of {} parts match the expected answers.", runs.len());
+        }
+        Ok(Command::Repl) => {
+            if let Err(e) = repl::run(&DAYS, &mut std::io::stdin().lock(), &mut std::io::stdout()) {
+                println!("Can't read commands: {e}\n");
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Ids { file, base, kind, overlaps, breakdown }) => {
+            if !day2_ids(file.as_deref(), base, &kind, overlaps, breakdown) {
+                println!();
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Lint { day, file }) => {
+            if !lint_day(day, file.as_deref()) {
+                println!();
+                std::process::exit(1);
+            }
+        }
+        Err(msg) => {
+            // invalid arguments
+            println!("{msg}\n{}\n", cli::USAGE);
+            std::process::exit(2);
         }
     }
     println!();
@@ -107,34 +537,14 @@ fn main() {
 
 #[cfg(test)]
 mod test {
-    use crate::day::Answer;
-    use crate::DAYS;
-    use lazy_static::lazy_static;
-    
-    lazy_static! {
-
-        static ref ANSWERS: [(Answer, Answer); 12] = [
-            (Answer::Numeric(1105), Answer::Numeric(6599)),                 // Dec 1
-            (Answer::Numeric(21139440284), Answer::Numeric(38731915928)),   // Dec 2
-            (Answer::Numeric(17193), Answer::Numeric(171297349921310)),     // Dec 3
-            (Answer::Numeric(1424), Answer::Numeric(8727)),                 // Dec 4
-            (Answer::Numeric(737), Answer::Numeric(357485433193284)),       // Dec 5
-            (Answer::Numeric(5733696195703), Answer::Numeric(10951882745757)),   // Dec 6
-            (Answer::Numeric(1524), Answer::Numeric(32982105837605)),   // Dec 7
-            (Answer::Numeric(50568), Answer::Numeric(36045012)),   // Dec 8
-            (Answer::Numeric(4744899849), Answer::Numeric(1540192500)),   // Part 1 > 4707216618
-            (Answer::Numeric(502), Answer::Numeric(21467)),   // Dec 10 21932 is too high, 21288 too low.
-            (Answer::Numeric(690), Answer::Numeric(557332758684000)),   // Dec 11
-            (Answer::Numeric(577), Answer::None),   // Dec 12)
-        ];
-    }
+    use crate::{DAYS, EXPECTED};
 
     #[test]
     fn test_all() {
         for day in 1..12 {
             let (d, text) = DAYS[day-1];
-            assert_eq!(d.part1(text), ANSWERS[day-1].0);
-            assert_eq!(d.part2(text), ANSWERS[day-1].1);
+            assert_eq!(d.part1(text), EXPECTED[day-1].0);
+            assert_eq!(d.part2(text), EXPECTED[day-1].1);
         }
     }
 
@@ -143,8 +553,8 @@ mod test {
         let day = 12;
         let (d, text) = DAYS[day-1];
 
-        assert_eq!(d.part1(text), ANSWERS[day-1].0);
-        assert_eq!(d.part2(text), ANSWERS[day-1].1);
+        assert_eq!(d.part1(text), EXPECTED[day-1].0);
+        assert_eq!(d.part2(text), EXPECTED[day-1].1);
     }
 
 }
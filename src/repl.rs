@@ -0,0 +1,237 @@
+// An interactive prompt for trying the solvers on inputs without recompiling.
+//
+//   day <n>                 pick a day, starting with its puzzle input
+//   load <file>             read the input from a file
+//   paste                   read the input from the lines that follow, up to a line with just "."
+//   show                    the input's size and first few lines
+//   1, 2                    solve a part of the input
+//   inspect                 what the day builds from the input
+//   lint                    check the input against the day's assumptions
+//   set strategy <name>     solve with a named strategy ("default" for the usual one)
+//   set overflow <policy>   checked, wide or big
+//   set trace on|off        show the trace notes after solving
+//   help, quit
+
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+use rs_aoc2025::arith::{self, Policy};
+use rs_aoc2025::day::{Day, DEFAULT_STRATEGY};
+use rs_aoc2025::trace;
+
+use crate::dash::format_micros;
+
+const HELP: &str = "\
+day <n>                 pick a day, starting with its puzzle input
+load <file>             read the input from a file
+paste                   read the input from the lines that follow, up to a line with just \".\"
+show                    the input's size and first few lines
+1, 2                    solve a part of the input
+inspect                 what the day builds from the input
+lint                    check the input against the day's assumptions
+set strategy <name>     solve with a named strategy (\"default\" for the usual one)
+set overflow <policy>   checked, wide or big
+set trace on|off        show the trace notes after solving
+help, quit";
+
+// Lines of input `show` prints.
+const SHOWN_LINES: usize = 5;
+
+struct Session<'a> {
+    days: &'a [(&'a dyn Day, &'a str)],
+    day: Option<usize>,
+    text: String,
+    strategy: String,
+    trace: bool,
+}
+
+impl<'a> Session<'a> {
+    fn new(days: &'a [(&'a dyn Day, &'a str)]) -> Session<'a> {
+        Session { days, day: None, text: String::new(), strategy: String::from(DEFAULT_STRATEGY), trace: false }
+    }
+
+    // The day picked, or a message saying to pick one.
+    fn day(&self) -> Result<(usize, &'a dyn Day), String> {
+        let day_no = self.day.ok_or("Pick a day first, with 'day <n>'.")?;
+
+        Ok((day_no, self.days[day_no-1].0))
+    }
+
+    // Run one command, reading any pasted input from `lines`.  Returns what to print, or None to quit.
+    fn eval(&mut self, command: &str, lines: &mut dyn BufRead) -> Option<String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let result = match words.as_slice() {
+            [] => Ok(String::new()),
+            ["quit"] | ["exit"] => return None,
+            ["help"] => Ok(HELP.to_string()),
+            ["day", n] => self.pick(n),
+            ["load", path] => {
+                std::fs::read_to_string(path)
+                    .map(|text| { self.set_input(text) })
+                    .map_err(|e| { format!("Can't read {path}: {e}") })
+            }
+            ["paste"] => self.paste(lines),
+            ["show"] => Ok(self.show()),
+            [part @ ("1" | "2")] => self.solve(if *part == "1" { 1 } else { 2 }),
+            ["inspect"] => self.inspect(),
+            ["lint"] => self.lint(),
+            ["set", "strategy", name] => {
+                self.strategy = name.to_string();
+                Ok(format!("Solving with strategy '{name}'."))
+            }
+            ["set", "overflow", value] => {
+                Policy::parse(value)
+                    .map(|policy| { arith::set_policy(policy); format!("Overflow policy is {value}.") })
+                    .ok_or(format!("Bad value '{value}' for overflow."))
+            }
+            ["set", "trace", value @ ("on" | "off")] => {
+                self.trace = *value == "on";
+                Ok(format!("Trace notes {value}."))
+            }
+            _ => Err(format!("Don't know '{command}'.  Try 'help'.")),
+        };
+
+        Some(result.unwrap_or_else(|message| { message }))
+    }
+
+    fn pick(&mut self, n: &str) -> Result<String, String> {
+        let day_no = match n.parse::<usize>() {
+            Ok(day_no) if (1..=self.days.len()).contains(&day_no) => day_no,
+            _ => return Err(format!("Day {n} is invalid.")),
+        };
+        self.day = Some(day_no);
+        self.text = self.days[day_no-1].1.to_string();
+
+        Ok(format!("Day {day_no}, with the puzzle input ({} lines.)", self.text.lines().count()))
+    }
+
+    fn set_input(&mut self, text: String) -> String {
+        self.text = text;
+        format!("Input is {} lines.", self.text.lines().count())
+    }
+
+    fn paste(&mut self, lines: &mut dyn BufRead) -> Result<String, String> {
+        let mut text = String::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let n = lines.read_line(&mut line).map_err(|e| { e.to_string() })?;
+            if n == 0 || line.trim_end() == "." {
+                break;
+            }
+            text.push_str(&line);
+        }
+
+        Ok(self.set_input(text))
+    }
+
+    fn show(&self) -> String {
+        let mut shown = format!("{} lines, {} bytes", self.text.lines().count(), self.text.len());
+        for line in self.text.lines().take(SHOWN_LINES) {
+            shown.push_str(&format!("\n  {line}"));
+        }
+        if self.text.lines().count() > SHOWN_LINES {
+            shown.push_str("\n  ...");
+        }
+
+        shown
+    }
+
+    fn solve(&self, part: usize) -> Result<String, String> {
+        let (day_no, day) = self.day()?;
+
+        let start = Instant::now();
+        let (result, notes) = trace::capture(|| { day.solve_with(part, &self.strategy, &self.text) });
+        let micros = start.elapsed().as_micros();
+
+        let mut out = match result {
+            Ok(ans) => format!("Day {day_no} part {part}: {ans} ({})", format_micros(micros)),
+            Err(e) => format!("Day {day_no} part {part}: {e}"),
+        };
+        if self.trace {
+            for note in notes {
+                out.push_str(&format!("\n  {note}"));
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn inspect(&self) -> Result<String, String> {
+        let (day_no, day) = self.day()?;
+
+        match day.inspect(&self.text) {
+            Some(Ok(text)) => Ok(text.trim_end().to_string()),
+            Some(Err(e)) => Err(format!("Day {day_no}: {e}")),
+            None => Err(format!("Day {day_no} has nothing to inspect.")),
+        }
+    }
+
+    fn lint(&self) -> Result<String, String> {
+        let (day_no, day) = self.day()?;
+
+        match day.lint(&self.text) {
+            Some(problems) if problems.is_empty() => Ok(String::from("No problems found.")),
+            Some(problems) => Ok(problems.iter().map(|p| { p.to_string() }).collect::<Vec<String>>().join("\n")),
+            None => Err(format!("Day {day_no} has no lint checks.")),
+        }
+    }
+}
+
+// Read commands from `input` until it ends or says quit, printing prompts and results to `output`.
+pub fn run(days: &[(&dyn Day, &str)], input: &mut dyn BufRead, output: &mut dyn Write) -> io::Result<()> {
+    let mut session = Session::new(days);
+    writeln!(output, "Type 'help' for commands.")?;
+
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(output)?;
+            return Ok(());
+        }
+        match session.eval(line.trim(), input) {
+            Some(text) if text.is_empty() => {}
+            Some(text) => writeln!(output, "{text}")?,
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::DAYS;
+    use crate::repl::run;
+
+    // What a session prints for the given input.
+    fn session(input: &str) -> String {
+        let mut output = Vec::new();
+        run(&DAYS, &mut input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_run() {
+        let out = session("1\nday 1\npaste\nL68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n.\nshow\n2\nquit\n1\n");
+        assert_eq!(out.lines().next(), Some("Type 'help' for commands."));
+        assert!(out.contains("> Pick a day first, with 'day <n>'.\n"));
+        assert!(out.contains("> Input is 10 lines.\n> 10 lines, 38 bytes\n  L68\n  L30\n  R48\n  L5\n  R60\n  ...\n"));
+        assert!(out.contains("> Day 1 part 2: 6 ("));
+        assert!(out.ends_with("> "));
+    }
+
+    #[test]
+    fn test_settings() {
+        let out = session("day 10\npaste\n[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n.\nset strategy linalg\n1\n\
+                           set strategy nonesuch\n1\nset overflow huge\nset trace maybe\ninspect\nlint\n");
+        assert!(out.contains("> Solving with strategy 'linalg'.\n> Day 10 part 1: 2 ("));
+        assert!(out.contains("> Day 10 part 1: part 1 has no strategy 'nonesuch'\n"));
+        assert!(out.contains("> Bad value 'huge' for overflow.\n"));
+        assert!(out.contains("> Don't know 'set trace maybe'.  Try 'help'.\n"));
+        assert!(out.contains("> Day 10 has nothing to inspect.\n"));
+        assert!(out.contains("> No problems found.\n"));
+    }
+}
@@ -0,0 +1,82 @@
+// A small seeded pseudo-random number generator (SplitMix64.)
+// Used to generate random puzzle inputs.  Not suitable for anything that needs real randomness.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in low..=high.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low <= high, "Empty range {low}..={high}");
+        let span = (high - low) as u64;
+        if span == u64::MAX {
+            return self.next_u64() as usize;
+        }
+
+        // Rejection sampling to avoid modulo bias.
+        let n = span + 1;
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let v = self.next_u64();
+            if v < zone {
+                return low + (v % n) as usize;
+            }
+        }
+    }
+
+    // Uniform value in 0..n.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0, n - 1)
+    }
+
+    // True with probability num/den.
+    pub fn chance(&mut self, num: usize, den: usize) -> bool {
+        self.below(den) < num
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0, i);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let v = rng.range(3, 8);
+            assert!((3..=8).contains(&v));
+            seen[v - 3] = true;
+        }
+        assert!(seen.iter().all(|s| { *s }));
+        assert_eq!(rng.range(5, 5), 5);
+    }
+}
@@ -0,0 +1,281 @@
+// A small HTTP server for the solvers, so other tools can use them without linking Rust.
+//
+//   GET  /days                  the registered days, with the strategies for each part
+//   POST /solve/<day>/<part>    solve a part, with the request body as the puzzle input
+//                               (?strategy=<name> picks a strategy other than the part's first)
+//
+// Responses are JSON.  Requests are handled one at a time, one per connection.  A solver that
+// panics gives a 500, and a client that stalls is dropped after IO_TIMEOUT.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use rs_aoc2025::day::{Day, SolveError, DEFAULT_STRATEGY};
+
+// Largest request body accepted.
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+// Longest wait for a client to send its request or take the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(PartialEq, Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    body: String,
+}
+
+// Quote a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+fn json_error(message: &str) -> String {
+    format!("{{\"error\":{}}}", json_string(message))
+}
+
+// Read one request: the request line, headers, then Content-Length bytes of body.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| { e.to_string() })?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else {
+        return Err(String::from("Bad request line."));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    let method = method.to_string();
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).map_err(|e| { e.to_string() })?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse::<usize>().map_err(|_| { String::from("Bad Content-Length.") })?;
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err(format!("Body over {MAX_BODY} bytes."));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| { e.to_string() })?;
+    let body = String::from_utf8(body).map_err(|_| { String::from("Body isn't UTF-8.") })?;
+
+    Ok(Request { method, path, query, body })
+}
+
+// Status code and JSON body for GET /days.
+fn list_days(days: &[(&dyn Day, &str)]) -> (u16, String) {
+    let list = |strategies: Vec<&str>| {
+        strategies.iter().map(|s| { json_string(s) }).collect::<Vec<String>>().join(",")
+    };
+    let entries: Vec<String> = days.iter().enumerate()
+        .map(|(n, (day, _))| {
+            format!("{{\"day\":{},\"part1\":[{}],\"part2\":[{}]}}", n + 1, list(day.strategies(1)), list(day.strategies(2)))
+        })
+        .collect();
+
+    (200, format!("{{\"days\":[{}]}}", entries.join(",")))
+}
+
+// Status code and JSON body for POST /solve/<day>/<part>.
+fn solve(days: &[(&dyn Day, &str)], day: &str, part: &str, query: Option<&str>, input: &str) -> (u16, String) {
+    let day_no = match day.parse::<usize>() {
+        Ok(n) if (1..=days.len()).contains(&n) => n,
+        _ => return (404, json_error(&format!("Day {day} is invalid."))),
+    };
+    let part_no = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => return (404, json_error(&format!("Part {part} is invalid."))),
+    };
+    let (day, _) = days[day_no-1];
+    let strategy = query.into_iter()
+        .flat_map(|q| { q.split('&') })
+        .find_map(|param| { param.strip_prefix("strategy=") })
+        .unwrap_or_else(|| { day.strategies(part_no).first().copied().unwrap_or(DEFAULT_STRATEGY) });
+
+    let start = Instant::now();
+    let result = day.solve_with(part_no, strategy, input);
+    let micros = start.elapsed().as_micros();
+
+    let (status, answer, error) = match result {
+        Ok(ans) => (200, json_string(&ans.to_string()), String::from("null")),
+        Err(e @ SolveError::NoStrategy { .. }) => (404, String::from("null"), json_string(&e.to_string())),
+        Err(e) => (422, String::from("null"), json_string(&e.to_string())),
+    };
+
+    (status, format!("{{\"day\":{day_no},\"part\":{part_no},\"strategy\":{},\"answer\":{answer},\"micros\":{micros},\"error\":{error}}}",
+                     json_string(strategy)))
+}
+
+// Status code and JSON body for a request.
+fn route(days: &[(&dyn Day, &str)], request: &Request) -> (u16, String) {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| { !s.is_empty() }).collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => list_days(days),
+        ("POST", ["solve", day, part]) => solve(days, day, part, request.query.as_deref(), &request.body),
+        (_, ["days"]) | (_, ["solve", _, _]) => (405, json_error(&format!("{} not allowed here.", request.method))),
+        _ => (404, json_error(&format!("No such endpoint: {}", request.path))),
+    }
+}
+
+// Status code and JSON body for a request, or a 500 if the solver panics.
+fn respond(days: &[(&dyn Day, &str)], request: &Request) -> (u16, String) {
+    catch_unwind(AssertUnwindSafe(|| { route(days, request) }))
+        .unwrap_or_else(|_| { (500, json_error("The solver panicked.")) })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+fn handle(stream: TcpStream, days: &[(&dyn Day, &str)]) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let (status, body) = match read_request(&mut reader) {
+        Ok(request) => respond(days, &request),
+        Err(message) => (400, json_error(&message)),
+    };
+
+    let mut stream = &stream;
+    write!(stream, "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+           reason(status), body.len())?;
+    stream.flush()
+}
+
+// Serve requests until the process is stopped.  A connection that fails doesn't stop the server.
+pub fn serve(listener: TcpListener, days: &[(&dyn Day, &str)]) {
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| { handle(stream, days) });
+        if let Err(e) = result {
+            eprintln!("Connection failed: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rs_aoc2025::day::{Day, Answer, SolveError};
+
+    use crate::DAYS;
+    use crate::server::{json_string, read_request, respond, route, Request};
+
+    const DAY1_EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+    const DAY10_EXAMPLE: &str = "\
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+";
+
+    // A day whose solver always panics.
+    struct Panics {
+    }
+
+    impl Day for Panics {
+        fn try_part1(&self, _input: &str) -> Result<Answer, SolveError> {
+            panic!("Out of cheese.");
+        }
+    }
+
+    fn request(method: &str, target: &str, body: &str) -> Request {
+        let text = format!("{method} {target} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+        read_request(&mut text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_read_request() {
+        let r = request("POST", "/solve/1/2?strategy=default", "L68\nR48\n");
+        assert_eq!(r, Request {
+            method: "POST".to_string(),
+            path: "/solve/1/2".to_string(),
+            query: Some("strategy=default".to_string()),
+            body: "L68\nR48\n".to_string(),
+        });
+
+        assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+        assert!(read_request(&mut "POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("line 3: Bad move 'X\"1'\n"), "\"line 3: Bad move 'X\\\"1'\\n\"");
+    }
+
+    #[test]
+    fn test_route() {
+        let days = &DAYS;
+
+        let (status, body) = route(days, &request("GET", "/days", ""));
+        assert_eq!(status, 200);
+        assert!(body.starts_with("{\"days\":[{\"day\":1,\"part1\":[\"default\"],\"part2\":[\"default\"]},"));
+        assert!(body.contains("{\"day\":10,\"part1\":[\"bfs\",\"linalg\"],\"part2\":[\"default\"]}"));
+
+        let (status, body) = route(days, &request("POST", "/solve/1/2", DAY1_EXAMPLE));
+        assert_eq!(status, 200);
+        assert!(body.starts_with("{\"day\":1,\"part\":2,\"strategy\":\"default\",\"answer\":\"6\",\"micros\":"));
+        assert!(body.ends_with(",\"error\":null}"));
+
+        let (status, body) = route(days, &request("POST", "/solve/1/1", "L68\nX30\n"));
+        assert_eq!(status, 422);
+        assert!(body.contains("\"answer\":null") && body.ends_with("\"error\":\"line 2: Bad move 'X30'\"}"));
+
+        // Without a strategy, a part is solved with its first, whatever that's called.
+        let (status, body) = route(days, &request("POST", "/solve/10/1", DAY10_EXAMPLE));
+        assert_eq!(status, 200);
+        assert!(body.starts_with("{\"day\":10,\"part\":1,\"strategy\":\"bfs\",\"answer\":\"7\","));
+        assert!(route(days, &request("POST", "/solve/10/1?strategy=linalg", DAY10_EXAMPLE)).1.contains("\"answer\":\"7\""));
+
+        assert_eq!(route(days, &request("POST", "/solve/10/1?strategy=nonesuch", "")).0, 404);
+        assert_eq!(route(days, &request("POST", "/solve/13/1", "")).0, 404);
+        assert_eq!(route(days, &request("POST", "/solve/1/3", "")).0, 404);
+        assert_eq!(route(days, &request("GET", "/solve/1/1", "")).0, 405);
+        assert_eq!(route(days, &request("GET", "/", "")).0, 404);
+    }
+
+    #[test]
+    // A panicking solver gives a 500, and the next request is still answered.
+    fn test_respond_panic() {
+        let days: [(&dyn Day, &str); 1] = [(&Panics { }, "")];
+        let (status, body) = respond(&days, &request("POST", "/solve/1/1", "x"));
+        assert_eq!(status, 500);
+        assert_eq!(body, "{\"error\":\"The solver panicked.\"}");
+        assert_eq!(respond(&days, &request("GET", "/days", "")).0, 200);
+    }
+}
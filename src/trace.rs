@@ -0,0 +1,66 @@
+// Tracing notes from the solvers.  Off unless something is capturing them, like the dashboard.
+//
+// Solvers pass note() a closure, so a note costs next to nothing when nobody's listening.
+
+use std::cell::RefCell;
+
+// Notes kept per capture.  Past this they're counted, not kept.
+const MAX_NOTES: usize = 10_000;
+
+#[derive(Default)]
+struct Notes {
+    kept: Vec<String>,
+    dropped: usize,
+}
+
+thread_local! {
+    static NOTES: RefCell<Option<Notes>> = const { RefCell::new(None) };
+}
+
+// Make a note, if notes on this thread are being captured.
+pub fn note(message: impl FnOnce() -> String) {
+    NOTES.with(|notes| {
+        if let Some(notes) = notes.borrow_mut().as_mut() {
+            if notes.kept.len() < MAX_NOTES {
+                notes.kept.push(message());
+            }
+            else {
+                notes.dropped += 1;
+            }
+        }
+    })
+}
+
+// Run f, returning its result along with the notes made on this thread while it ran.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let outer = NOTES.with(|notes| { notes.replace(Some(Notes::default())) });
+    let result = f();
+    let notes = NOTES.with(|notes| { notes.replace(outer) }).unwrap_or_default();
+
+    let mut kept = notes.kept;
+    if notes.dropped > 0 {
+        kept.push(format!("({} more notes not kept)", notes.dropped));
+    }
+
+    (result, kept)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::trace::{capture, note};
+
+    #[test]
+    fn test_capture() {
+        // Not captured, so the closure never runs.
+        note(|| { panic!("Shouldn't be called.") });
+
+        let (answer, notes) = capture(|| {
+            note(|| { String::from("outer") });
+            let (_, inner) = capture(|| { note(|| { String::from("inner") }) });
+            assert_eq!(inner, vec!["inner"]);
+            42
+        });
+        assert_eq!(answer, 42);
+        assert_eq!(notes, vec!["outer"]);
+    }
+}
@@ -0,0 +1,146 @@
+// Drawing helpers for Day::visualize().
+//
+// A visualization is a list of frames, oldest first, each either ASCII art or an SVG image.
+// One frame is a picture; more are an animation, the last being the final state.
+
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Style {
+    Ascii,
+    Svg,
+}
+
+impl Style {
+    // File extension for frames in this style.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Style::Ascii => "txt",
+            Style::Svg => "svg",
+        }
+    }
+}
+
+// Fill colors, for things like pieces or networks told apart by number.
+pub const PALETTE: [&str; 8] = ["#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7"];
+
+// A grid of characters, for ASCII frames.
+pub struct Canvas {
+    width: usize,
+    cells: Vec<char>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Canvas {
+        Canvas { width, cells: vec![' '; width * height] }
+    }
+
+    // Put a character at (x, y).  Off the canvas, it's dropped.
+    pub fn set(&mut self, x: usize, y: usize, c: char) {
+        if x < self.width && y < self.cells.len() / self.width.max(1) {
+            self.cells[y * self.width + x] = c;
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> char {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for row in self.cells.chunks(self.width.max(1)) {
+            text.extend(row.iter());
+            text.truncate(text.trim_end_matches(' ').len());
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+// An SVG image being drawn, in user units.
+pub struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl Svg {
+    pub fn new(width: f64, height: f64) -> Svg {
+        Svg { width, height, body: String::new() }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+        let _ = writeln!(self.body, "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"{fill}\"/>");
+    }
+
+    pub fn circle(&mut self, x: f64, y: f64, r: f64, fill: &str) {
+        let _ = writeln!(self.body, "<circle cx=\"{x}\" cy=\"{y}\" r=\"{r}\" fill=\"{fill}\"/>");
+    }
+
+    pub fn line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), stroke: &str, width: f64) {
+        let _ = writeln!(self.body, "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{stroke}\" stroke-width=\"{width}\"/>");
+    }
+
+    // A closed outline through the points.
+    pub fn polygon(&mut self, points: &[(f64, f64)], fill: &str, stroke: &str, width: f64) {
+        let points: Vec<String> = points.iter().map(|(x, y)| { format!("{x},{y}") }).collect();
+        let _ = writeln!(self.body, "<polygon points=\"{}\" fill=\"{fill}\" stroke=\"{stroke}\" stroke-width=\"{width}\"/>",
+                         points.join(" "));
+    }
+
+    // Text with its baseline starting at (x, y).  Markup characters are escaped.
+    pub fn text(&mut self, x: f64, y: f64, size: f64, text: &str) {
+        let text = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let _ = writeln!(self.body, "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" font-family=\"sans-serif\">{text}</text>");
+    }
+
+    pub fn finish(self) -> String {
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">\n{}</svg>\n",
+                self.width, self.height, self.width, self.height, self.body)
+    }
+}
+
+// Map v in lo..=hi onto 0..steps-1, for fitting big coordinates onto a small canvas.
+pub fn scale(v: usize, lo: usize, hi: usize, steps: usize) -> usize {
+    if hi <= lo || steps == 0 {
+        return 0;
+    }
+    ((v - lo) as u128 * (steps - 1) as u128 / (hi - lo) as u128) as usize
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vis::{scale, Canvas, Svg};
+
+    #[test]
+    fn test_canvas() {
+        // Trailing spaces are trimmed, and drawing off the edge is ignored.
+        let mut canvas = Canvas::new(4, 2);
+        canvas.set(0, 0, '#');
+        canvas.set(2, 1, '@');
+        canvas.set(4, 0, 'X');
+        canvas.set(0, 2, 'X');
+        assert_eq!(canvas.get(2, 1), '@');
+        assert_eq!(canvas.render(), "#\n  @\n");
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(10.0, 5.0);
+        svg.rect(1.0, 2.0, 3.0, 1.5, "red");
+        svg.text(0.0, 4.0, 2.0, "a<b");
+        assert_eq!(svg.finish(), "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 10 5\" width=\"10\" height=\"5\">\n\
+                                  <rect x=\"1\" y=\"2\" width=\"3\" height=\"1.5\" fill=\"red\"/>\n\
+                                  <text x=\"0\" y=\"4\" font-size=\"2\" font-family=\"sans-serif\">a&lt;b</text>\n\
+                                  </svg>\n");
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(scale(0, 0, 100_000, 80), 0);
+        assert_eq!(scale(100_000, 0, 100_000, 80), 79);
+        assert_eq!(scale(50_000, 0, 100_000, 81), 40);
+        assert_eq!(scale(7, 7, 7, 80), 0);
+    }
+}
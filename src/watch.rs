@@ -0,0 +1,143 @@
+// Watch mode: re-run a day whenever the files it depends on change.
+//
+// The files watched are the day's source (which holds its example fixtures and their tests) and
+// its input: a file given on the command line, or the data crate holding the puzzle inputs.
+// Changes to either need a rebuild, so each run goes through cargo: the day's tests first, then
+// both parts, checked against the expected answers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// How often to look for changes.
+const POLL: Duration = Duration::from_millis(500);
+
+// Modification time and length of every file under a path, or None for paths that are missing.
+type Stamps = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+fn stamp(path: &Path, stamps: &mut Stamps) {
+    let Ok(meta) = fs::metadata(path) else {
+        stamps.push((path.to_path_buf(), None));
+        return;
+    };
+
+    if meta.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        let mut children: Vec<PathBuf> = entries.flatten()
+            .map(|entry| { entry.path() })
+            .filter(|child| { !child.ends_with("target") && !child.ends_with(".git") })
+            .collect();
+        children.sort();
+        for child in children {
+            stamp(&child, stamps);
+        }
+    }
+    else {
+        let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        stamps.push((path.to_path_buf(), Some((modified, meta.len()))));
+    }
+}
+
+fn stamps(paths: &[PathBuf]) -> Stamps {
+    let mut stamps = Vec::new();
+    for path in paths {
+        stamp(path, &mut stamps);
+    }
+
+    stamps
+}
+
+// The files a day depends on.
+fn watched(day: usize, file: Option<&str>) -> Vec<PathBuf> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let input = match file {
+        Some(path) => PathBuf::from(path),
+        None => manifest_dir.join("../data_aoc2025"),
+    };
+
+    vec![manifest_dir.join(format!("src/day{day}.rs")), input]
+}
+
+// Run the day's tests, then report both parts with their answers checked.
+fn rerun(day: usize, file: Option<&str>) {
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    let cargo = std::env::var("CARGO").unwrap_or(String::from("cargo"));
+
+    println!("\n=== Day {day} ===\n");
+
+    let tests = Command::new(&cargo)
+        .args(["test", "-q", "--manifest-path", manifest, "--lib"])
+        .arg(format!("day{day}::"))
+        .status();
+    match tests {
+        Ok(status) if status.success() => println!("Tests passed."),
+        Ok(_) => println!("Tests FAILED."),
+        Err(e) => {
+            println!("Can't run {cargo}: {e}");
+            return;
+        }
+    }
+
+    let mut report = Command::new(&cargo);
+    report.args(["run", "-q", "--release", "--manifest-path", manifest, "--"])
+        .arg(day.to_string())
+        .arg("--check");
+    if let Some(path) = file {
+        report.args(["--input", path]);
+    }
+    if let Err(e) = report.status() {
+        println!("Can't run {cargo}: {e}");
+    }
+}
+
+// Run the day, then again each time its files change.  Only returns by the process being stopped.
+pub fn run(day: usize, file: Option<&str>) -> ! {
+    let paths = watched(day, file);
+    println!("Watching {}", paths.iter().map(|p| { p.display().to_string() }).collect::<Vec<String>>().join(", "));
+
+    let mut last = stamps(&paths);
+    rerun(day, file);
+    loop {
+        thread::sleep(POLL);
+        let now = stamps(&paths);
+        if now != last {
+            last = now;
+            rerun(day, file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::watch::stamps;
+
+    #[test]
+    fn test_stamps() {
+        // A change to any file under a watched directory is noticed, as is a missing file appearing.
+        let dir = std::env::temp_dir().join(format!("rs_aoc2025_watch_{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/input.txt"), "L68\n").unwrap();
+        let missing = dir.join("later.txt");
+        let paths = vec![dir.clone(), missing.clone()];
+
+        let before = stamps(&paths);
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[1], (missing.clone(), None));
+        assert_eq!(stamps(&paths), before);
+
+        fs::write(dir.join("sub/input.txt"), "L68\nR48\n").unwrap();
+        let edited = stamps(&paths);
+        assert_ne!(edited, before);
+
+        fs::write(&missing, "").unwrap();
+        assert_ne!(stamps(&paths), edited);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}